@@ -0,0 +1,176 @@
+use crate::map::Map;
+
+const SIDES: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Measurements for ranking and comparing mazes. Cells are open cells of the
+// grid; the start and exits are not counted as dead ends.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MazeMetrics {
+    pub open_cells: usize,
+    pub solution_length: Option<usize>, // Steps on the shortest route, None if unsolvable
+    pub dead_ends: usize,               // Cells with one open side
+    pub junctions: usize,               // Cells with three or more open sides
+    pub branching_factor: f32,          // Average choices offered at a junction
+    pub average_corridor_length: f32,   // Average steps between junctions and dead ends
+    pub river_factor: f32,              // Average length of a dead-end branch
+    pub tortuosity: Option<f32>,        // Solution length over straight-line distance
+    pub solution_coverage: f32,         // Percent of open cells on the solution
+}
+
+impl Map {
+    // Analyse the maze's structure and its shortest solution
+    pub fn metrics(&self) -> MazeMetrics {
+        let mut metrics = MazeMetrics::default();
+
+        // Cells where a corridor ends: dead ends, junctions, start and exits
+        let mut nodes = vec![];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.move_cost(x, y).is_none() {
+                    continue;
+                }
+                metrics.open_cells += 1;
+                let degree = self.open_sides(x, y).len();
+                let endpoint = (x, y) == self.start || self.is_goal((x, y));
+                if degree == 1 && !endpoint {
+                    metrics.dead_ends += 1;
+                } else if degree >= 3 {
+                    metrics.junctions += 1;
+                    metrics.branching_factor += (degree - 1) as f32;
+                }
+                if degree != 2 || endpoint {
+                    nodes.push((x, y));
+                }
+            }
+        }
+        if metrics.junctions > 0 {
+            metrics.branching_factor /= metrics.junctions as f32;
+        }
+
+        // Walk every corridor from both of its ends
+        let is_dead_end = |(x, y): (usize, usize)| {
+            self.open_sides(x, y).len() == 1 && (x, y) != self.start && !self.is_goal((x, y))
+        };
+        let mut corridor_steps = 0;
+        let mut corridor_count = 0;
+        let mut branch_steps = 0;
+        for &node in &nodes {
+            for next in self.open_sides(node.0, node.1) {
+                let (end, steps) = self.follow_corridor(node, next);
+                corridor_steps += steps;
+                corridor_count += 1;
+
+                // A dead-end branch runs from a dead end to a junction
+                if is_dead_end(node) && self.open_sides(end.0, end.1).len() >= 3 {
+                    branch_steps += steps;
+                }
+            }
+        }
+        if corridor_count > 0 {
+            metrics.average_corridor_length = corridor_steps as f32 / corridor_count as f32;
+        }
+        if metrics.dead_ends > 0 {
+            metrics.river_factor = branch_steps as f32 / metrics.dead_ends as f32;
+        }
+
+        if let Some(path) = self.solve() {
+            let length = path.len() - 1;
+            let (first, last) = (path[0], path[length]);
+            let distance = first.0.abs_diff(last.0) + first.1.abs_diff(last.1);
+            metrics.solution_length = Some(length);
+            metrics.tortuosity = Some(length as f32 / distance.max(1) as f32);
+            metrics.solution_coverage = path.len() as f32 * 100.0 / metrics.open_cells as f32;
+        }
+
+        metrics
+    }
+
+    // Open cells next to (x, y)
+    fn open_sides(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        SIDES
+            .iter()
+            .map(|&(dx, dy)| (x.wrapping_add(dx as usize), y.wrapping_add(dy as usize)))
+            .filter(|&(nx, ny)| self.move_cost(nx, ny).is_some())
+            .collect()
+    }
+
+    // Walk from `from` through `next` along cells with exactly two open
+    // sides, returning the cell where the corridor ends and the steps taken
+    fn follow_corridor(
+        &self,
+        from: (usize, usize),
+        next: (usize, usize),
+    ) -> ((usize, usize), usize) {
+        let (mut previous, mut current) = (from, next);
+        let mut steps = 1;
+        loop {
+            let sides = self.open_sides(current.0, current.1);
+            let endpoint = current == self.start || self.is_goal(current);
+            if sides.len() != 2 || endpoint || current == from {
+                return (current, steps);
+            }
+            let following = if sides[0] == previous {
+                sides[1]
+            } else {
+                sides[0]
+            };
+            previous = current;
+            current = following;
+            steps += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::from_rows;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn measures_a_small_maze() {
+        // One junction below the start: right to the exit, down and round
+        // to a dead end
+        let map = Map::from_ascii(
+            "\
+#S#E#
+#   #
+# ###
+#   #
+#####
+",
+        )
+        .unwrap();
+        let metrics = map.metrics();
+        assert_eq!(metrics.open_cells, 9);
+        assert_eq!(metrics.solution_length, Some(4));
+        assert_eq!(metrics.dead_ends, 1);
+        assert_eq!(metrics.junctions, 1);
+        assert_close(metrics.branching_factor, 2.0);
+        // Corridors of 1, 3 and 4 steps, each walked from both ends
+        assert_close(metrics.average_corridor_length, 16.0 / 6.0);
+        assert_close(metrics.river_factor, 4.0);
+        assert_close(metrics.tortuosity.unwrap(), 2.0);
+        assert_close(metrics.solution_coverage, 500.0 / 9.0);
+    }
+
+    #[test]
+    fn unsolvable_maze_has_no_solution_metrics() {
+        let mut map = from_rows(&["#####", "# # #", "#####"]);
+        map.start = (1, 1);
+        map.goals = vec![(3, 1)];
+        let metrics = map.metrics();
+        assert_eq!(metrics.open_cells, 2);
+        assert_eq!(metrics.solution_length, None);
+        assert_eq!(metrics.tortuosity, None);
+        assert_eq!(metrics.solution_coverage, 0.0);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::cell::Cell;
+use crate::map::Map;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Why a text map was rejected, pointing at the offending character.
+// Lines and columns count from 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsciiError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl AsciiError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for AsciiError {}
+
+impl From<AsciiError> for io::Error {
+    fn from(error: AsciiError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+impl Map {
+    // Render the map as text: `#` wall, space path, `S` start, `E` goal and
+    // `.` for cells on the given solution path
+    pub fn to_ascii(&self, solution: Option<&[(usize, usize)]>) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let position = (x, y);
+                let symbol = if position == self.start {
+                    'S'
+                } else if self.is_goal(position) {
+                    'E'
+                } else if self.get(x, y) == Some(Cell::Wall) {
+                    '#'
+                } else if solution.is_some_and(|path| path.contains(&position)) {
+                    '.'
+                } else {
+                    ' '
+                };
+                text.push(symbol);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    // Parse a map written in the format produced by `to_ascii`. Every line
+    // must be the same width, there must be exactly one `S` and at least one
+    // `E`, and an `E` must be reachable from the `S`. `.` is read as a path.
+    pub fn from_ascii(text: &str) -> Result<Self, AsciiError> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        // Ignore blank lines after the maze, which editors often add
+        let height = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |last| last + 1);
+        if height == 0 {
+            return Err(AsciiError::new(1, 1, "the map is empty"));
+        }
+        let width = lines[0].chars().count();
+
+        let mut grid = Vec::with_capacity(width * height);
+        let mut start = None;
+        let mut goals = vec![];
+        for (y, line) in lines[..height].iter().enumerate() {
+            let mut length = 0;
+            for (x, symbol) in line.chars().enumerate() {
+                length += 1;
+                if x >= width {
+                    continue;
+                }
+                let cell = match symbol {
+                    '#' => Cell::Wall,
+                    ' ' | '.' => Cell::Path,
+                    'S' => {
+                        if let Some((sx, sy)) = start {
+                            return Err(AsciiError::new(
+                                y + 1,
+                                x + 1,
+                                format!(
+                                    "second start; the first is at line {}, column {}",
+                                    sy + 1,
+                                    sx + 1
+                                ),
+                            ));
+                        }
+                        start = Some((x, y));
+                        Cell::Path
+                    }
+                    'E' => {
+                        goals.push((x, y));
+                        Cell::Path
+                    }
+                    other => {
+                        return Err(AsciiError::new(
+                            y + 1,
+                            x + 1,
+                            format!("unexpected character {:?}", other),
+                        ))
+                    }
+                };
+                grid.push(cell);
+            }
+            if length != width {
+                return Err(AsciiError::new(
+                    y + 1,
+                    length.min(width) + 1,
+                    format!("line is {} characters wide, expected {}", length, width),
+                ));
+            }
+        }
+
+        let Some(start) = start else {
+            return Err(AsciiError::new(1, 1, "no start (S) in the map"));
+        };
+        let Some(&first_goal) = goals.first() else {
+            return Err(AsciiError::new(1, 1, "no exit (E) in the map"));
+        };
+
+        let map = Map::from_grid(width, height, grid, start, goals)
+            .map_err(|e| AsciiError::new(1, 1, e.to_string()))?;
+        if map.solve().is_none() {
+            return Err(AsciiError::new(
+                first_goal.1 + 1,
+                first_goal.0 + 1,
+                "no exit can be reached from the start",
+            ));
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{error, maze};
+    use crate::format;
+    use crate::generators::Algorithm;
+
+    // Where and why a map must be rejected
+    fn parse_error(text: &str) -> (usize, usize, String) {
+        let e = error(Map::from_ascii(text));
+        (e.line, e.column, e.message)
+    }
+
+    #[test]
+    fn round_trip() {
+        let map = maze(15, 11, Algorithm::Prim, 5);
+        let text = map.to_ascii(None);
+        let parsed = Map::from_ascii(&text).unwrap();
+        assert_eq!(parsed.content_hash(), map.content_hash());
+        assert_eq!(parsed.to_ascii(None), text);
+    }
+
+    #[test]
+    fn solution_dots_read_as_path() {
+        let map = Map::from_ascii("#S#\n#.#\n#E#\n").unwrap();
+        assert!(map.get(1, 1) == Some(Cell::Path));
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let map = Map::from_ascii("#S#\r\n# #\r\n#E#\r\n").unwrap();
+        assert_eq!((map.width, map.height), (3, 3));
+        assert_eq!(map.goals, vec![(1, 2)]);
+    }
+
+    #[test]
+    fn ragged_line() {
+        let (line, column, message) = parse_error("#S#\n# \n#E#\n");
+        assert_eq!((line, column), (2, 3));
+        assert!(message.contains("2 characters wide, expected 3"));
+    }
+
+    #[test]
+    fn blank_line_in_the_middle() {
+        let (line, column, message) = parse_error("#S#\n\n#E#\n");
+        assert_eq!((line, column), (2, 1));
+        assert!(message.contains("0 characters wide"));
+    }
+
+    #[test]
+    fn bad_character() {
+        let (line, column, message) = parse_error("#S#\n#x#\n#E#\n");
+        assert_eq!((line, column), (2, 2));
+        assert!(message.contains("'x'"));
+    }
+
+    #[test]
+    fn two_starts() {
+        let (line, column, message) = parse_error("#S#\n#S#\n#E#\n");
+        assert_eq!((line, column), (2, 2));
+        assert!(message.contains("line 1, column 2"));
+    }
+
+    #[test]
+    fn no_exit() {
+        let (_, _, message) = parse_error("#S#\n# #\n###\n");
+        assert!(message.contains("no exit"));
+    }
+
+    #[test]
+    fn unreachable_exit() {
+        let (line, column, message) = parse_error("#S#\n###\n#E#\n");
+        assert_eq!((line, column), (3, 2));
+        assert!(message.contains("can be reached"));
+    }
+
+    #[test]
+    fn too_small_to_save() {
+        let (_, _, message) = parse_error("SE\n");
+        assert!(message.contains("at least 3x3"));
+    }
+
+    #[test]
+    fn parsed_maps_can_be_saved_and_loaded() {
+        let map = Map::from_ascii("#S#\n# #\n#E#\n").unwrap();
+        let loaded = format::decode(&format::encode(&map).unwrap()).unwrap();
+        assert_eq!(loaded.content_hash(), map.content_hash());
+    }
+}
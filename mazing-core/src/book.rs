@@ -0,0 +1,114 @@
+use crate::difficulty::Difficulty;
+use crate::generators::Algorithm;
+use crate::map::Map;
+use crate::pdf::{Page, Pdf, PAGE_HEIGHT, PAGE_WIDTH};
+
+const MARGIN: f32 = 50.0;
+const TITLE_SPACE: f32 = 60.0; // Room above each maze for its title
+
+// What goes into a printable puzzle book
+#[derive(Clone, Debug, PartialEq)]
+pub struct BookOptions {
+    pub title: String,
+    pub count: usize, // Number of mazes
+    pub width: usize,
+    pub height: usize,
+    pub algorithm: Algorithm,
+    pub braid: f32,
+    pub seed: u64, // Maze `i` is generated from `seed + i`
+}
+
+impl Default for BookOptions {
+    fn default() -> Self {
+        Self {
+            title: "Maze Book".to_string(),
+            count: 10,
+            width: 21,
+            height: 29,
+            algorithm: Algorithm::RecursiveBacktracker,
+            braid: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+// Generate the book's mazes in order
+pub fn book_mazes(options: &BookOptions) -> Vec<Map> {
+    (0..options.count)
+        .map(|i| {
+            let mut map = Map::new(options.width, options.height);
+            map.generate_maze(options.algorithm, options.seed.wrapping_add(i as u64));
+            map.braid(options.braid);
+            map
+        })
+        .collect()
+}
+
+// Build a PDF with one page per maze, each titled with its number and
+// difficulty, followed by an answer page per maze with the solution drawn in
+pub fn puzzle_book(options: &BookOptions) -> Vec<u8> {
+    let mazes = book_mazes(options);
+    let mut pdf = Pdf::new();
+
+    for (i, map) in mazes.iter().enumerate() {
+        let mut page = Page::new();
+        let difficulty = match map.metadata.difficulty {
+            Some(score) => format!("{} ({:.0})", Difficulty::of_score(score).name(), score),
+            None => "Unsolvable".to_string(),
+        };
+        page.text(MARGIN, MARGIN, 12.0, &options.title);
+        page.text(
+            MARGIN,
+            MARGIN + 30.0,
+            20.0,
+            &format!("Maze {} - {}", i + 1, difficulty),
+        );
+        draw_maze(&mut page, map, false);
+        pdf.add_page(page);
+    }
+
+    for (i, map) in mazes.iter().enumerate() {
+        let mut page = Page::new();
+        page.text(MARGIN, MARGIN, 12.0, &options.title);
+        page.text(
+            MARGIN,
+            MARGIN + 30.0,
+            20.0,
+            &format!("Answer to maze {}", i + 1),
+        );
+        draw_maze(&mut page, map, true);
+        pdf.add_page(page);
+    }
+
+    pdf.to_bytes()
+}
+
+// Draw a maze centred in the space under the title, optionally with its
+// shortest solution
+fn draw_maze(page: &mut Page, map: &Map, with_solution: bool) {
+    let area_width = PAGE_WIDTH - 2.0 * MARGIN;
+    let area_height = PAGE_HEIGHT - 2.0 * MARGIN - TITLE_SPACE;
+    let cell_size = (area_width / map.width as f32).min(area_height / map.height as f32);
+    let left = MARGIN + (area_width - cell_size * map.width as f32) / 2.0;
+    let top = MARGIN + TITLE_SPACE + (area_height - cell_size * map.height as f32) / 2.0;
+    let centre = |(x, y): (usize, usize)| {
+        (
+            left + (x as f32 + 0.5) * cell_size,
+            top + (y as f32 + 0.5) * cell_size,
+        )
+    };
+
+    let walls: Vec<_> = map
+        .wall_segments()
+        .iter()
+        .map(|segment| (centre(segment.from), centre(segment.to)))
+        .collect();
+    page.lines(&walls, cell_size * 0.3, [0.0, 0.0, 0.0], true);
+
+    if with_solution {
+        if let Some(path) = map.solve() {
+            let points: Vec<_> = path.iter().map(|&position| centre(position)).collect();
+            page.polyline(&points, cell_size * 0.3, [0.85, 0.1, 0.1]);
+        }
+    }
+}
@@ -4,5 +4,5 @@ use serde::{Deserialize, Serialize};
 pub enum Cell {
     Wall,
     Path,
-    Solution, // Marks the solution path
+    Solution, // Legacy solution marker from older saves; walkable like Path
 }
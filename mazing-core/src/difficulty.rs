@@ -0,0 +1,210 @@
+use crate::analysis::MazeMetrics;
+use crate::generators::Algorithm;
+use crate::map::Map;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+// Difficulty levels players choose from instead of a raw size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        }
+    }
+
+    // Range of `difficulty_score` a maze must fall in to count as this level
+    pub fn band(self) -> Range<f32> {
+        match self {
+            Difficulty::Easy => 0.0..60.0,
+            Difficulty::Medium => 60.0..140.0,
+            Difficulty::Hard => 140.0..300.0,
+            Difficulty::Expert => 300.0..f32::INFINITY,
+        }
+    }
+
+    // Level a score falls in
+    pub fn of_score(score: f32) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.band().contains(&score))
+            .unwrap_or(Difficulty::Expert)
+    }
+
+    // Maze sizes tried when searching for this level, in order. Most mazes
+    // of these sizes score within the band, whatever the algorithm.
+    fn sizes(self) -> &'static [(usize, usize)] {
+        match self {
+            Difficulty::Easy => &[(11, 11), (13, 13), (15, 11), (15, 15)],
+            Difficulty::Medium => &[(21, 15), (21, 21), (19, 15), (25, 19)],
+            Difficulty::Hard => &[(31, 25), (35, 25), (41, 31)],
+            Difficulty::Expert => &[(51, 41), (61, 45), (71, 51)],
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("unknown difficulty '{}'", name))
+    }
+}
+
+// How hard a maze is to solve: every step of the shortest route, every dead
+// end to back out of and every junction to choose at adds a point. None if
+// the maze cannot be solved.
+pub fn difficulty_score(metrics: &MazeMetrics) -> Option<f32> {
+    let length = metrics.solution_length?;
+    Some((length + metrics.dead_ends + metrics.junctions) as f32)
+}
+
+// A maze of a given difficulty, built with the given algorithm and braiding
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultyRequest {
+    pub difficulty: Difficulty,
+    pub algorithm: Algorithm,
+    pub braid: f32,
+    pub seed: u64,                // Base seed; candidates use seeds derived from it
+    pub attempts: usize,          // Candidates to try before settling for the closest
+    pub budget: Option<Duration>, // Also give up, with the closest so far, after this
+}
+
+impl DifficultyRequest {
+    pub fn new(difficulty: Difficulty, algorithm: Algorithm, seed: u64) -> Self {
+        Self {
+            difficulty,
+            algorithm,
+            braid: 0.0,
+            seed,
+            attempts: 64,
+            budget: None,
+        }
+    }
+}
+
+// Generate candidates in a fixed order of sizes and seeds until one falls in
+// the requested band. The search stops after `attempts` candidates, or once
+// the budget (if any) has run out, whichever comes first; then the one
+// scoring closest to the band is returned. Without a budget the same request
+// always gives the same maze; with one, a slow machine may stop earlier.
+pub fn generate_for_difficulty(request: &DifficultyRequest) -> Map {
+    let started = Instant::now();
+    let mut closest: Option<(f32, Map)> = None;
+
+    for attempt in 0..request.attempts.max(1) {
+        let map = candidate(request, attempt);
+        let distance = distance_from_band(request.difficulty, &map);
+        if distance == 0.0 {
+            return map;
+        }
+        if closest.as_ref().is_none_or(|(best, _)| distance < *best) {
+            closest = Some((distance, map));
+        }
+        if request
+            .budget
+            .is_some_and(|budget| started.elapsed() >= budget)
+        {
+            break;
+        }
+    }
+
+    // At least one attempt always runs
+    closest.map(|(_, map)| map).unwrap()
+}
+
+// The nth maze tried for a request
+fn candidate(request: &DifficultyRequest, attempt: usize) -> Map {
+    let sizes = request.difficulty.sizes();
+    let (width, height) = sizes[attempt % sizes.len()];
+    let mut map = Map::new(width, height);
+    map.generate_maze(request.algorithm, candidate_seed(request.seed, attempt));
+    map.braid(request.braid);
+    map
+}
+
+// How far a maze's score is outside the level's band, 0 when inside it
+fn distance_from_band(difficulty: Difficulty, map: &Map) -> f32 {
+    let band = difficulty.band();
+    match map.metadata.difficulty {
+        Some(score) if band.contains(&score) => 0.0,
+        Some(score) => (band.start - score).max(score - band.end),
+        None => f32::INFINITY,
+    }
+}
+
+// Seed for the nth candidate: a SplitMix64 step, so neighbouring base seeds
+// don't share candidates
+fn candidate_seed(seed: u64, attempt: usize) -> u64 {
+    let mut z = seed.wrapping_add((attempt as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_request_gives_same_maze() {
+        for difficulty in Difficulty::ALL {
+            let mut request = DifficultyRequest::new(difficulty, Algorithm::Prim, 9);
+            request.braid = 0.2;
+            let first = generate_for_difficulty(&request);
+            let second = generate_for_difficulty(&request);
+            assert_eq!(first.content_hash(), second.content_hash());
+        }
+    }
+
+    #[test]
+    fn settles_for_the_closest_when_attempts_run_out() {
+        // Without dead ends, mazes of the two smallest Expert sizes score
+        // too low for it
+        let mut request =
+            DifficultyRequest::new(Difficulty::Expert, Algorithm::RecursiveBacktracker, 4);
+        request.braid = 1.0;
+        request.attempts = 2;
+        let distances: Vec<f32> = (0..request.attempts)
+            .map(|attempt| distance_from_band(request.difficulty, &candidate(&request, attempt)))
+            .collect();
+        assert!(distances.iter().all(|&distance| distance > 0.0));
+        assert_ne!(distances[0], distances[1]);
+
+        let best = distances.iter().copied().fold(f32::INFINITY, f32::min);
+        let map = generate_for_difficulty(&request);
+        assert_eq!(distance_from_band(request.difficulty, &map), best);
+    }
+
+    #[test]
+    fn budget_stops_the_search_early() {
+        let mut request = DifficultyRequest::new(Difficulty::Expert, Algorithm::BinaryTree, 4);
+        request.braid = 1.0;
+        let unlimited = generate_for_difficulty(&request);
+        request.budget = Some(Duration::ZERO);
+        let map = generate_for_difficulty(&request);
+        assert_eq!(map.content_hash(), candidate(&request, 0).content_hash());
+        assert_ne!(map.content_hash(), unlimited.content_hash());
+    }
+}
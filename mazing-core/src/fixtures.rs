@@ -0,0 +1,45 @@
+// Maps and helpers shared by the unit tests
+
+use crate::cell::Cell;
+use crate::generators::Algorithm;
+use crate::map::Map;
+
+// A generated maze with a fraction of its dead ends braided away
+pub fn braided(width: usize, height: usize, algorithm: Algorithm, seed: u64, braid: f32) -> Map {
+    let mut map = maze(width, height, algorithm, seed);
+    map.braid(braid);
+    map
+}
+
+// A maze of the given size generated from a seed
+pub fn maze(width: usize, height: usize, algorithm: Algorithm, seed: u64) -> Map {
+    let mut map = Map::new(width, height);
+    map.generate_maze(algorithm, seed);
+    map
+}
+
+// The error of a result that must have failed. Maps aren't `Debug`, so
+// `unwrap_err` can't be used on them.
+pub fn error<T, E>(result: Result<T, E>) -> E {
+    match result {
+        Ok(_) => panic!("expected an error"),
+        Err(e) => e,
+    }
+}
+
+// A map drawn as rows of `#` for walls and anything else for paths, with
+// the start in the top left corner and no exits
+pub fn from_rows(rows: &[&str]) -> Map {
+    let grid = rows
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|symbol| {
+            if symbol == '#' {
+                Cell::Wall
+            } else {
+                Cell::Path
+            }
+        })
+        .collect();
+    Map::from_grid(rows[0].len(), rows.len(), grid, (0, 0), vec![]).unwrap()
+}
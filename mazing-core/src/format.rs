@@ -0,0 +1,295 @@
+// On-disk map format.
+//
+// A file is a fixed header followed by the bincode-encoded map:
+//
+//   magic    4 bytes  "MAZE"
+//   version  u16 LE   FORMAT_VERSION when written
+//   length   u32 LE   size of the payload in bytes
+//   checksum u32 LE   CRC-32 of the payload
+//   payload
+//
+// Files without the magic are legacy saves, raw bincode of the original
+// `Map { width, height, grid }`. Older files are upgraded to the current
+// format the next time they are saved.
+//
+// Versions:
+//   1  grid, generation parameters, start and goals
+//   2  generation parameters moved into a metadata block with name, author,
+//      creation time, difficulty and tags
+
+use crate::cell::Cell;
+use crate::generators::Algorithm;
+use crate::map::Map;
+use crate::metadata::Metadata;
+use serde::Deserialize;
+use std::io;
+
+pub const MAGIC: &[u8; 4] = b"MAZE";
+pub const FORMAT_VERSION: u16 = 2;
+const HEADER_LEN: usize = 14;
+
+// Map layout written before the format had a header
+#[derive(Deserialize)]
+struct LegacyMap {
+    width: usize,
+    height: usize,
+    grid: Vec<Cell>,
+}
+
+impl From<LegacyMap> for Map {
+    fn from(legacy: LegacyMap) -> Self {
+        // Legacy maps always used the default entrance and exit
+        let mut map = Map::new(legacy.width, legacy.height);
+        map.grid = legacy.grid;
+        map.metadata = Metadata::default();
+        map
+    }
+}
+
+// Version 1 payload
+#[derive(Deserialize)]
+struct MapV1 {
+    width: usize,
+    height: usize,
+    grid: Vec<Cell>,
+    seed: u64,
+    algorithm: Algorithm,
+    braid: f32,
+    start: (usize, usize),
+    goals: Vec<(usize, usize)>,
+}
+
+impl From<MapV1> for Map {
+    fn from(v1: MapV1) -> Self {
+        let mut map = Map::new(v1.width, v1.height);
+        map.grid = v1.grid;
+        map.start = v1.start;
+        map.goals = v1.goals;
+        map.metadata = Metadata {
+            algorithm: Some(v1.algorithm),
+            seed: v1.seed,
+            braid: v1.braid,
+            ..Metadata::default()
+        };
+        map
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Encode a map with the current header
+pub fn encode(map: &Map) -> io::Result<Vec<u8>> {
+    let payload = bincode::serialize(map).map_err(io::Error::other)?;
+    let length = u32::try_from(payload.len())
+        .map_err(|_| invalid_data("map is too large to save".to_string()))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&length.to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+// Decode a map in the current format, an older versioned format or the
+// legacy headerless format
+pub fn decode(bytes: &[u8]) -> io::Result<Map> {
+    if !bytes.starts_with(MAGIC) {
+        let legacy: LegacyMap = bincode::deserialize(bytes).map_err(|e| {
+            invalid_data(format!("not a map file (no header, legacy decode: {})", e))
+        })?;
+        check_dimensions(legacy.width, legacy.height)?;
+        return upgrade(legacy.into());
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(invalid_data("truncated map header".to_string()));
+    }
+
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    let length = u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]) as usize;
+    let checksum = u32::from_le_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]);
+    let payload = &bytes[HEADER_LEN..];
+
+    if version > FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "map format version {} is newer than supported version {}",
+            version, FORMAT_VERSION
+        )));
+    }
+    if payload.len() != length {
+        return Err(invalid_data(format!(
+            "map payload is {} bytes, header says {}",
+            payload.len(),
+            length
+        )));
+    }
+    if crc32fast::hash(payload) != checksum {
+        return Err(invalid_data("map checksum mismatch".to_string()));
+    }
+
+    match version {
+        1 => {
+            let v1: MapV1 =
+                bincode::deserialize(payload).map_err(|e| invalid_data(e.to_string()))?;
+            check_dimensions(v1.width, v1.height)?;
+            upgrade(v1.into())
+        }
+        2 => check(bincode::deserialize(payload).map_err(|e| invalid_data(e.to_string()))?),
+        _ => Err(invalid_data(format!(
+            "unknown map format version {}",
+            version
+        ))),
+    }
+}
+
+// Check a map converted from an older format and rate it, since older
+// formats had no difficulty
+fn upgrade(map: Map) -> io::Result<Map> {
+    let mut map = check(map)?;
+    map.metadata.difficulty = map.difficulty();
+    Ok(map)
+}
+
+// Reject sizes no maze fits in. Older formats are checked before they are
+// converted, since `Map::new` assumes at least 3x3.
+pub(crate) fn check_dimensions(width: usize, height: usize) -> io::Result<()> {
+    if width < 3 || height < 3 {
+        return Err(invalid_data(format!(
+            "map is {}x{}, at least 3x3 is needed",
+            width, height
+        )));
+    }
+    Ok(())
+}
+
+// Reject maps that are too small, whose grid does not match their
+// dimensions, or whose start or exits lie outside the grid
+pub(crate) fn check(map: Map) -> io::Result<Map> {
+    check_dimensions(map.width, map.height)?;
+    if Some(map.grid.len()) != map.width.checked_mul(map.height) {
+        return Err(invalid_data(format!(
+            "grid has {} cells, expected {}x{}",
+            map.grid.len(),
+            map.width,
+            map.height
+        )));
+    }
+    let inside = |(x, y): (usize, usize)| x < map.width && y < map.height;
+    if !inside(map.start) {
+        return Err(invalid_data(format!(
+            "start {:?} is outside the {}x{} grid",
+            map.start, map.width, map.height
+        )));
+    }
+    if let Some(goal) = map.goals.iter().find(|&&goal| !inside(goal)) {
+        return Err(invalid_data(format!(
+            "exit {:?} is outside the {}x{} grid",
+            goal, map.width, map.height
+        )));
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{error, maze};
+
+    fn small_maze() -> Map {
+        maze(11, 9, Algorithm::RecursiveBacktracker, 7)
+    }
+
+    // A header of the given version around a payload
+    fn framed(version: u16, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    // The message of a decode that must fail
+    fn decode_error(bytes: &[u8]) -> String {
+        let e = error(decode(bytes));
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        e.to_string()
+    }
+
+    #[test]
+    fn round_trip() {
+        let map = small_maze();
+        let decoded = decode(&encode(&map).unwrap()).unwrap();
+        assert_eq!(decoded.content_hash(), map.content_hash());
+        assert_eq!(decoded.metadata, map.metadata);
+    }
+
+    #[test]
+    fn corrupt_checksum() {
+        let mut bytes = encode(&small_maze()).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(decode_error(&bytes).contains("checksum"));
+    }
+
+    #[test]
+    fn truncated_header() {
+        let bytes = encode(&small_maze()).unwrap();
+        assert!(decode_error(&bytes[..HEADER_LEN - 1]).contains("truncated"));
+    }
+
+    #[test]
+    fn newer_version() {
+        let mut bytes = encode(&small_maze()).unwrap();
+        bytes[4..6].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(decode_error(&bytes).contains("newer"));
+    }
+
+    #[test]
+    fn legacy_headerless() {
+        let map = small_maze();
+        let bytes = bincode::serialize(&(map.width, map.height, &map.grid)).unwrap();
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.content_hash(), map.content_hash());
+        assert_eq!(decoded.metadata.difficulty, map.difficulty());
+    }
+
+    #[test]
+    fn too_small_in_every_version() {
+        let legacy = bincode::serialize(&(1usize, 1usize, vec![Cell::Wall])).unwrap();
+        assert!(decode_error(&legacy).contains("at least 3x3"));
+
+        let v1 = (
+            1usize,
+            5usize,
+            vec![Cell::Wall; 5],
+            0u64,
+            Algorithm::RecursiveBacktracker,
+            0f32,
+            (0usize, 0usize),
+            vec![(0usize, 4usize)],
+        );
+        let v1 = framed(1, &bincode::serialize(&v1).unwrap());
+        assert!(decode_error(&v1).contains("at least 3x3"));
+
+        let mut map = small_maze();
+        map.width = 2;
+        map.grid.truncate(2 * map.height);
+        let v2 = framed(2, &bincode::serialize(&map).unwrap());
+        assert!(decode_error(&v2).contains("at least 3x3"));
+    }
+
+    #[test]
+    fn start_and_exits_inside_the_grid() {
+        let mut map = small_maze();
+        map.start = (map.width, 0);
+        assert!(decode_error(&encode(&map).unwrap()).contains("start"));
+
+        let mut map = small_maze();
+        map.goals.push((0, map.height));
+        assert!(decode_error(&encode(&map).unwrap()).contains("exit"));
+    }
+}
@@ -0,0 +1,25 @@
+use super::{carve_passage, cells, neighbors, random_cell, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Aldous-Broder: a plain random walk that carves into unvisited cells.
+// Uniform like Wilson's, but slow to finish on large maps.
+pub struct AldousBroder;
+
+impl MazeGenerator for AldousBroder {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let mut remaining = cells(map).len() - 1;
+        let mut current = random_cell(map, rng);
+        map.set(current.0, current.1, Cell::Path);
+
+        while remaining > 0 {
+            let next = *neighbors(map, current).choose(rng).unwrap();
+            if map.get(next.0, next.1) == Some(Cell::Wall) {
+                carve_passage(map, current, next);
+                remaining -= 1;
+            }
+            current = next;
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use super::{random_cell, unvisited_neighbors, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Depth-first search with an explicit stack; long winding corridors
+pub struct RecursiveBacktracker;
+
+impl MazeGenerator for RecursiveBacktracker {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let (start_x, start_y) = random_cell(map, rng);
+
+        // Mark starting point as path
+        map.set(start_x, start_y, Cell::Path);
+        let mut open_cells = vec![(start_x, start_y)];
+
+        // Main loop for generating the maze
+        while let Some((x, y)) = open_cells.pop() {
+            let neighbors = unvisited_neighbors(map, (x, y));
+            if let Some(&(nx, ny)) = neighbors.choose(rng) {
+                open_cells.push((x, y));
+                map.set(nx, ny, Cell::Path);
+                map.set((x + nx) / 2, (y + ny) / 2, Cell::Path);
+                open_cells.push((nx, ny));
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use super::{carve_passage, cells, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Binary Tree: every cell opens either north or west. Fast, with long open
+// corridors along the top row and left column.
+pub struct BinaryTree;
+
+impl MazeGenerator for BinaryTree {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        for (x, y) in cells(map) {
+            map.set(x, y, Cell::Path);
+
+            let mut options = Vec::with_capacity(2);
+            if y > 1 {
+                options.push((x, y - 2));
+            }
+            if x > 1 {
+                options.push((x - 2, y));
+            }
+            if let Some(&next) = options.choose(rng) {
+                carve_passage(map, (x, y), next);
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::{cell_dims, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Recursive division: a wall-adding generator. It opens the whole interior
+// and splits chambers with straight walls that each get a single gap,
+// producing long straight corridors.
+pub struct RecursiveDivision {
+    // Chance (0-100) of splitting with a horizontal wall, before accounting
+    // for the chamber's shape. 50 follows the chamber's proportions, higher
+    // values favour long east-west corridors, lower ones north-south.
+    pub horizontal_bias: u8,
+}
+
+impl Default for RecursiveDivision {
+    fn default() -> Self {
+        Self {
+            horizontal_bias: 50,
+        }
+    }
+}
+
+impl MazeGenerator for RecursiveDivision {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        // Open everything inside the outer wall
+        for y in 1..map.height - 1 {
+            for x in 1..map.width - 1 {
+                map.set(x, y, Cell::Path);
+            }
+        }
+
+        // Chambers in cell coordinates: (left, top, columns, rows)
+        let (cols, rows) = cell_dims(map);
+        let mut chambers = vec![(0, 0, cols, rows)];
+
+        while let Some((left, top, cols, rows)) = chambers.pop() {
+            if cols < 2 || rows < 2 {
+                continue;
+            }
+
+            if self.split_horizontally(cols, rows, rng) {
+                // Wall below cell row `top + split`, with one gap
+                let split = rng.gen_range(0..rows - 1);
+                let wall_y = (top + split) * 2 + 2;
+                for x in left * 2 + 1..(left + cols) * 2 {
+                    map.set(x, wall_y, Cell::Wall);
+                }
+                let gap = rng.gen_range(left..left + cols);
+                map.set(gap * 2 + 1, wall_y, Cell::Path);
+
+                chambers.push((left, top, cols, split + 1));
+                chambers.push((left, top + split + 1, cols, rows - split - 1));
+            } else {
+                // Wall right of cell column `left + split`, with one gap
+                let split = rng.gen_range(0..cols - 1);
+                let wall_x = (left + split) * 2 + 2;
+                for y in top * 2 + 1..(top + rows) * 2 {
+                    map.set(wall_x, y, Cell::Wall);
+                }
+                let gap = rng.gen_range(top..top + rows);
+                map.set(wall_x, gap * 2 + 1, Cell::Path);
+
+                chambers.push((left, top, split + 1, rows));
+                chambers.push((left + split + 1, top, cols - split - 1, rows));
+            }
+        }
+    }
+}
+
+impl RecursiveDivision {
+    // Tall chambers prefer horizontal walls and wide ones vertical walls;
+    // the bias shifts that balance
+    fn split_horizontally(&self, cols: usize, rows: usize, rng: &mut MazeRng) -> bool {
+        let bias = self.horizontal_bias.min(100) as f64;
+        let horizontal = bias * rows as f64;
+        let vertical = (100.0 - bias) * cols as f64;
+        rng.gen_bool(horizontal / (horizontal + vertical))
+    }
+}
@@ -0,0 +1,64 @@
+use super::{carve_passage, cell_at, cell_dims, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Eller's: builds the maze one row at a time, tracking which cells of the
+// current row are already connected
+pub struct Eller;
+
+impl MazeGenerator for Eller {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let (cols, rows) = cell_dims(map);
+        let mut row_sets: Vec<Option<usize>> = vec![None; cols];
+        let mut next_set = 0;
+
+        for cy in 0..rows {
+            let last_row = cy + 1 == rows;
+
+            // Cells not joined from the row above start in their own set
+            for (cx, set) in row_sets.iter_mut().enumerate() {
+                if set.is_none() {
+                    *set = Some(next_set);
+                    next_set += 1;
+                }
+                let (x, y) = cell_at(cx, cy);
+                map.set(x, y, Cell::Path);
+            }
+
+            // Randomly join adjacent cells of different sets; the last row
+            // joins all of them so the maze ends up connected
+            for cx in 0..cols.saturating_sub(1) {
+                if row_sets[cx] != row_sets[cx + 1] && (last_row || rng.gen_bool(0.5)) {
+                    carve_passage(map, cell_at(cx, cy), cell_at(cx + 1, cy));
+                    let (kept, merged) = (row_sets[cx], row_sets[cx + 1]);
+                    for set in row_sets.iter_mut().filter(|set| **set == merged) {
+                        *set = kept;
+                    }
+                }
+            }
+
+            if last_row {
+                break;
+            }
+
+            // Every set carries at least one passage down to the next row
+            let mut next_row = vec![None; cols];
+            let mut sets: Vec<usize> = row_sets.iter().flatten().copied().collect();
+            sets.sort_unstable();
+            sets.dedup();
+            for set in sets {
+                let mut members: Vec<usize> =
+                    (0..cols).filter(|&cx| row_sets[cx] == Some(set)).collect();
+                members.shuffle(rng);
+                for (i, &cx) in members.iter().enumerate() {
+                    if i == 0 || rng.gen_bool(1.0 / 3.0) {
+                        carve_passage(map, cell_at(cx, cy), cell_at(cx, cy + 1));
+                        next_row[cx] = Some(set);
+                    }
+                }
+            }
+            row_sets = next_row;
+        }
+    }
+}
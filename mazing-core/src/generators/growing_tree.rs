@@ -0,0 +1,40 @@
+use super::{carve_passage, random_cell, unvisited_neighbors, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Growing Tree: keeps a list of active cells and extends from either the
+// newest one (backtracker-like) or a random one (Prim-like)
+pub struct GrowingTree {
+    pub newest_ratio: f64, // Chance of picking the newest active cell
+}
+
+impl Default for GrowingTree {
+    fn default() -> Self {
+        Self { newest_ratio: 0.5 }
+    }
+}
+
+impl MazeGenerator for GrowingTree {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let start = random_cell(map, rng);
+        map.set(start.0, start.1, Cell::Path);
+        let mut active = vec![start];
+
+        while !active.is_empty() {
+            let index = if rng.gen_bool(self.newest_ratio) {
+                active.len() - 1
+            } else {
+                rng.gen_range(0..active.len())
+            };
+            let cell = active[index];
+
+            if let Some(&next) = unvisited_neighbors(map, cell).choose(rng) {
+                carve_passage(map, cell, next);
+                active.push(next);
+            } else {
+                active.remove(index);
+            }
+        }
+    }
+}
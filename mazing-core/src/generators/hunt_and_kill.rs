@@ -0,0 +1,42 @@
+use super::{
+    carve_passage, cells, random_cell, unvisited_neighbors, visited_neighbors, MazeGenerator,
+};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Hunt-and-Kill: random walk until stuck, then scan for an unvisited cell
+// next to the maze and continue from there
+pub struct HuntAndKill;
+
+impl MazeGenerator for HuntAndKill {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let cells = cells(map);
+        let mut current = Some(random_cell(map, rng));
+        if let Some((x, y)) = current {
+            map.set(x, y, Cell::Path);
+        }
+
+        while let Some(cell) = current {
+            // Kill: walk to a random unvisited neighbour
+            if let Some(&next) = unvisited_neighbors(map, cell).choose(rng) {
+                carve_passage(map, cell, next);
+                current = Some(next);
+                continue;
+            }
+
+            // Hunt: first unvisited cell that borders the maze
+            current = None;
+            for &candidate in &cells {
+                if map.get(candidate.0, candidate.1) != Some(Cell::Wall) {
+                    continue;
+                }
+                if let Some(&from) = visited_neighbors(map, candidate).choose(rng) {
+                    carve_passage(map, from, candidate);
+                    current = Some(candidate);
+                    break;
+                }
+            }
+        }
+    }
+}
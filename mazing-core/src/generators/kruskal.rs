@@ -0,0 +1,46 @@
+use super::{carve_passage, cell_index, cells, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Randomized Kruskal's: joins cells along shuffled walls unless they are
+// already connected
+pub struct Kruskal;
+
+impl MazeGenerator for Kruskal {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let cells = cells(map);
+        let mut parent: Vec<usize> = (0..cells.len()).collect();
+
+        // Every wall between two horizontally or vertically adjacent cells
+        let mut edges = Vec::new();
+        for &(x, y) in &cells {
+            map.set(x, y, Cell::Path);
+            if x + 2 < map.width {
+                edges.push(((x, y), (x + 2, y)));
+            }
+            if y + 2 < map.height {
+                edges.push(((x, y), (x, y + 2)));
+            }
+        }
+        edges.shuffle(rng);
+
+        for (a, b) in edges {
+            let root_a = find(&mut parent, cell_index(map, a));
+            let root_b = find(&mut parent, cell_index(map, b));
+            if root_a != root_b {
+                parent[root_b] = root_a;
+                carve_passage(map, a, b);
+            }
+        }
+    }
+}
+
+// Find the set representative, compressing the path along the way
+fn find(parent: &mut [usize], mut index: usize) -> usize {
+    while parent[index] != index {
+        parent[index] = parent[parent[index]];
+        index = parent[index];
+    }
+    index
+}
@@ -0,0 +1,215 @@
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+mod aldous_broder;
+mod backtracker;
+mod binary_tree;
+mod division;
+mod eller;
+mod growing_tree;
+mod hunt_and_kill;
+mod kruskal;
+mod prim;
+mod sidewinder;
+mod wilson;
+
+pub use aldous_broder::AldousBroder;
+pub use backtracker::RecursiveBacktracker;
+pub use binary_tree::BinaryTree;
+pub use division::RecursiveDivision;
+pub use eller::Eller;
+pub use growing_tree::GrowingTree;
+pub use hunt_and_kill::HuntAndKill;
+pub use kruskal::Kruskal;
+pub use prim::Prim;
+pub use sidewinder::Sidewinder;
+pub use wilson::Wilson;
+
+// A maze generation algorithm. Generators receive a map filled with walls and
+// connect the cells at odd coordinates, leaving the outer wall intact.
+// Entrance and exit are opened by the caller.
+pub trait MazeGenerator {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng);
+}
+
+// Selectable generation algorithms, stored in saved maps
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    AldousBroder,
+    Eller,
+    HuntAndKill,
+    BinaryTree,
+    Sidewinder,
+    GrowingTree,
+    RecursiveDivision { horizontal_bias: u8 },
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 13] = [
+        Algorithm::RecursiveBacktracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Wilson,
+        Algorithm::AldousBroder,
+        Algorithm::Eller,
+        Algorithm::HuntAndKill,
+        Algorithm::BinaryTree,
+        Algorithm::Sidewinder,
+        Algorithm::GrowingTree,
+        Algorithm::RecursiveDivision {
+            horizontal_bias: 50,
+        },
+        Algorithm::RecursiveDivision {
+            horizontal_bias: 80,
+        },
+        Algorithm::RecursiveDivision {
+            horizontal_bias: 20,
+        },
+    ];
+
+    // Human readable name for menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "Recursive Backtracker",
+            Algorithm::Prim => "Prim's",
+            Algorithm::Kruskal => "Kruskal's",
+            Algorithm::Wilson => "Wilson's",
+            Algorithm::AldousBroder => "Aldous-Broder",
+            Algorithm::Eller => "Eller's",
+            Algorithm::HuntAndKill => "Hunt-and-Kill",
+            Algorithm::BinaryTree => "Binary Tree",
+            Algorithm::Sidewinder => "Sidewinder",
+            Algorithm::GrowingTree => "Growing Tree",
+            Algorithm::RecursiveDivision { horizontal_bias } => match horizontal_bias {
+                0..=39 => "Recursive Division (Vertical)",
+                40..=60 => "Recursive Division",
+                _ => "Recursive Division (Horizontal)",
+            },
+        }
+    }
+
+    // Short name used on the command line
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "backtracker",
+            Algorithm::Prim => "prim",
+            Algorithm::Kruskal => "kruskal",
+            Algorithm::Wilson => "wilson",
+            Algorithm::AldousBroder => "aldous-broder",
+            Algorithm::Eller => "eller",
+            Algorithm::HuntAndKill => "hunt-and-kill",
+            Algorithm::BinaryTree => "binary-tree",
+            Algorithm::Sidewinder => "sidewinder",
+            Algorithm::GrowingTree => "growing-tree",
+            Algorithm::RecursiveDivision { horizontal_bias } => match horizontal_bias {
+                0..=39 => "division-vertical",
+                40..=60 => "division",
+                _ => "division-horizontal",
+            },
+        }
+    }
+
+    // Build the generator implementing this algorithm
+    pub fn generator(&self) -> Box<dyn MazeGenerator> {
+        match self {
+            Algorithm::RecursiveBacktracker => Box::new(RecursiveBacktracker),
+            Algorithm::Prim => Box::new(Prim),
+            Algorithm::Kruskal => Box::new(Kruskal),
+            Algorithm::Wilson => Box::new(Wilson),
+            Algorithm::AldousBroder => Box::new(AldousBroder),
+            Algorithm::Eller => Box::new(Eller),
+            Algorithm::HuntAndKill => Box::new(HuntAndKill),
+            Algorithm::BinaryTree => Box::new(BinaryTree),
+            Algorithm::Sidewinder => Box::new(Sidewinder),
+            Algorithm::GrowingTree => Box::new(GrowingTree::default()),
+            Algorithm::RecursiveDivision { horizontal_bias } => Box::new(RecursiveDivision {
+                horizontal_bias: *horizontal_bias,
+            }),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    // Parse a command line name, as given by `slug`
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Algorithm::ALL
+            .iter()
+            .find(|algorithm| algorithm.slug() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown algorithm '{}'", name))
+    }
+}
+
+// Number of maze cells horizontally and vertically
+fn cell_dims(map: &Map) -> (usize, usize) {
+    (map.width / 2, map.height / 2)
+}
+
+// Grid coordinates of the maze cell at column `cx`, row `cy`
+fn cell_at(cx: usize, cy: usize) -> (usize, usize) {
+    (cx * 2 + 1, cy * 2 + 1)
+}
+
+// Grid coordinates of every maze cell, row by row
+fn cells(map: &Map) -> Vec<(usize, usize)> {
+    let (cols, rows) = cell_dims(map);
+    (0..rows)
+        .flat_map(|cy| (0..cols).map(move |cx| cell_at(cx, cy)))
+        .collect()
+}
+
+// Index of a maze cell, for algorithms that keep per-cell state
+fn cell_index(map: &Map, (x, y): (usize, usize)) -> usize {
+    (y / 2) * (map.width / 2) + x / 2
+}
+
+// Maze cells adjacent to (x, y), two grid steps away
+fn neighbors(map: &Map, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+    let mut neighbors = vec![];
+    for &(dx, dy) in &[(-2isize, 0), (2, 0), (0, -2), (0, 2)] {
+        let nx = x.wrapping_add(dx as usize);
+        let ny = y.wrapping_add(dy as usize);
+        if nx < map.width && ny < map.height {
+            neighbors.push((nx, ny));
+        }
+    }
+    neighbors
+}
+
+// Neighbouring cells that have not been carved yet
+fn unvisited_neighbors(map: &Map, pos: (usize, usize)) -> Vec<(usize, usize)> {
+    neighbors(map, pos)
+        .into_iter()
+        .filter(|&(x, y)| map.get(x, y) == Some(Cell::Wall))
+        .collect()
+}
+
+// Neighbouring cells that are already part of the maze
+fn visited_neighbors(map: &Map, pos: (usize, usize)) -> Vec<(usize, usize)> {
+    neighbors(map, pos)
+        .into_iter()
+        .filter(|&(x, y)| map.get(x, y) != Some(Cell::Wall))
+        .collect()
+}
+
+// Open both cells and the wall between them
+fn carve_passage(map: &mut Map, (ax, ay): (usize, usize), (bx, by): (usize, usize)) {
+    map.set(ax, ay, Cell::Path);
+    map.set((ax + bx) / 2, (ay + by) / 2, Cell::Path);
+    map.set(bx, by, Cell::Path);
+}
+
+// A random cell to start carving from
+fn random_cell(map: &Map, rng: &mut MazeRng) -> (usize, usize) {
+    let (cols, rows) = cell_dims(map);
+    cell_at(rng.gen_range(0..cols), rng.gen_range(0..rows))
+}
@@ -0,0 +1,38 @@
+use super::{
+    carve_passage, cell_index, random_cell, unvisited_neighbors, visited_neighbors, MazeGenerator,
+};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Randomized Prim's: grows the maze from a random frontier cell each step,
+// giving lots of short dead ends
+pub struct Prim;
+
+impl MazeGenerator for Prim {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let mut in_frontier = vec![false; (map.width / 2) * (map.height / 2)];
+        let start = random_cell(map, rng);
+        map.set(start.0, start.1, Cell::Path);
+
+        let mut frontier = unvisited_neighbors(map, start);
+        for &cell in &frontier {
+            in_frontier[cell_index(map, cell)] = true;
+        }
+
+        while !frontier.is_empty() {
+            let cell = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            if let Some(&from) = visited_neighbors(map, cell).choose(rng) {
+                carve_passage(map, from, cell);
+            }
+
+            for next in unvisited_neighbors(map, cell) {
+                let index = cell_index(map, next);
+                if !in_frontier[index] {
+                    in_frontier[index] = true;
+                    frontier.push(next);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use super::{carve_passage, cell_at, cell_dims, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Sidewinder: carves horizontal runs and closes each one with a single
+// passage north; the top row is one long corridor
+pub struct Sidewinder;
+
+impl MazeGenerator for Sidewinder {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let (cols, rows) = cell_dims(map);
+
+        for cy in 0..rows {
+            let mut run_start = 0;
+            for cx in 0..cols {
+                let (x, y) = cell_at(cx, cy);
+                map.set(x, y, Cell::Path);
+
+                let at_east = cx + 1 == cols;
+                let close_run = cy > 0 && (at_east || rng.gen_bool(0.5));
+                if close_run {
+                    let member = rng.gen_range(run_start..=cx);
+                    carve_passage(map, cell_at(member, cy), cell_at(member, cy - 1));
+                    run_start = cx + 1;
+                } else if !at_east {
+                    carve_passage(map, (x, y), cell_at(cx + 1, cy));
+                }
+            }
+        }
+    }
+}
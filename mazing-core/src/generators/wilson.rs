@@ -0,0 +1,46 @@
+use super::{carve_passage, cell_index, cells, neighbors, random_cell, MazeGenerator};
+use crate::cell::Cell;
+use crate::map::{Map, MazeRng};
+use rand::prelude::*;
+
+// Wilson's: loop-erased random walks produce an unbiased uniform spanning tree
+pub struct Wilson;
+
+impl MazeGenerator for Wilson {
+    fn generate(&self, map: &mut Map, rng: &mut MazeRng) {
+        let mut remaining = cells(map);
+        remaining.shuffle(rng);
+
+        let mut in_maze = vec![false; remaining.len()];
+        let first = random_cell(map, rng);
+        map.set(first.0, first.1, Cell::Path);
+        in_maze[cell_index(map, first)] = true;
+
+        // Direction taken out of each cell during the current walk
+        let mut next_step: Vec<Option<(usize, usize)>> = vec![None; remaining.len()];
+
+        for start in remaining {
+            if in_maze[cell_index(map, start)] {
+                continue;
+            }
+
+            // Random walk until the maze is hit; overwriting the exit of a
+            // revisited cell erases the loop
+            let mut current = start;
+            while !in_maze[cell_index(map, current)] {
+                let next = *neighbors(map, current).choose(rng).unwrap();
+                next_step[cell_index(map, current)] = Some(next);
+                current = next;
+            }
+
+            // Add the loop-erased path to the maze
+            let mut current = start;
+            while !in_maze[cell_index(map, current)] {
+                let next = next_step[cell_index(map, current)].unwrap();
+                carve_passage(map, current, next);
+                in_maze[cell_index(map, current)] = true;
+                current = next;
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// Racing against the best earlier run on a map. A run's trace is where the
+// player was when each move started and ended; the fastest one is kept
+// beside the map and played back as a ghost.
+
+use crate::map::Map;
+use crate::session::{Session, TICK};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+
+// The player leaving or reaching a cell
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TracePoint {
+    pub tick: u64, // Ticks since the run's first move
+    pub position: (usize, usize),
+}
+
+// Timed positions of one run. Between a point and the next the player is
+// either standing still, if both are the same cell, or moving evenly
+// between them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    pub map_hash: u64, // `Map::content_hash` of the map it was made on
+    pub points: Vec<TracePoint>,
+}
+
+impl Trace {
+    // File the best trace for the map at `map_path` is kept in, beside the map
+    pub fn path_for(map_path: &str) -> String {
+        format!("{}.ghost", map_path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), io::Error> {
+        let bytes = bincode::serialize(self).map_err(io::Error::other)?;
+        fs::write(path, bytes)
+    }
+
+    pub fn load(path: &str) -> Result<Self, io::Error> {
+        let bytes = fs::read(path)?;
+        bincode::deserialize(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // Save over the trace at `path` unless that one is a faster run on the
+    // same maze; returns whether this trace was saved
+    pub fn save_if_best(&self, path: &str) -> Result<bool, io::Error> {
+        if let Ok(best) = Self::load(path) {
+            if best.map_hash == self.map_hash && best.ticks() <= self.ticks() {
+                return Ok(false);
+            }
+        }
+        self.save(path)?;
+        Ok(true)
+    }
+
+    // Length of the run in ticks
+    pub fn ticks(&self) -> u64 {
+        self.points.last().map_or(0, |point| point.tick)
+    }
+
+    // Length of the run in seconds
+    pub fn duration(&self) -> f32 {
+        self.ticks() as f32 * TICK
+    }
+
+    // Where the player was `tick` ticks into the run, between cells while
+    // moving. None for an empty trace.
+    pub fn position_at(&self, tick: u64) -> Option<(f32, f32)> {
+        let index = self
+            .points
+            .partition_point(|point| point.tick <= tick)
+            .checked_sub(1)?;
+        let from = self.points[index];
+        let (x, y) = (from.position.0 as f32, from.position.1 as f32);
+        match self.points.get(index + 1) {
+            Some(to) if to.position != from.position => {
+                let t = (tick - from.tick) as f32 / (to.tick - from.tick) as f32;
+                Some((
+                    x + (to.position.0 as f32 - x) * t,
+                    y + (to.position.1 as f32 - y) * t,
+                ))
+            }
+            _ => Some((x, y)),
+        }
+    }
+
+    // When the player first reached a cell, in ticks into the run
+    pub fn arrival(&self, position: (usize, usize)) -> Option<u64> {
+        self.points
+            .iter()
+            .find(|point| point.position == position)
+            .map(|point| point.tick)
+    }
+}
+
+// A ghost racing a live session
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ghost {
+    pub trace: Trace,
+    pub position: (f32, f32),      // Where the ghost is drawn, in cells
+    pub delta: Option<f32>,        // Seconds the player is behind the ghost, negative when ahead
+    last_position: (usize, usize), // Cell the player was last compared at
+}
+
+impl Ghost {
+    // Race against the best trace saved for the map at `map_path`, if there
+    // is one for this maze
+    pub fn load(map_path: &str, map: &Map) -> Option<Self> {
+        let trace = Trace::load(&Trace::path_for(map_path)).ok()?;
+        if trace.map_hash != map.content_hash() || trace.points.is_empty() {
+            return None;
+        }
+        Some(Self {
+            position: (map.start.0 as f32, map.start.1 as f32),
+            trace,
+            delta: None,
+            last_position: map.start,
+        })
+    }
+
+    // Move the ghost to where it was at the same point of its run, and
+    // compare times whenever the player reaches a cell the ghost passed.
+    // The ghost waits at the start until the player's first move.
+    pub fn update(&mut self, session: &Session) {
+        let tick = session.run_ticks().unwrap_or(0);
+        if let Some(position) = self.trace.position_at(tick) {
+            self.position = position;
+        }
+
+        let position = session.player.position;
+        if session.player.is_moving || position == self.last_position {
+            return;
+        }
+        self.last_position = position;
+        if let Some(arrival) = self.trace.arrival(position) {
+            self.delta = Some((tick as f32 - arrival as f32) * TICK);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn trace(points: &[(u64, (usize, usize))]) -> Trace {
+        Trace {
+            map_hash: 1,
+            points: points
+                .iter()
+                .map(|&(tick, position)| TracePoint { tick, position })
+                .collect(),
+        }
+    }
+
+    // Down a cell, a pause, then right a cell
+    fn run() -> Trace {
+        trace(&[(0, (1, 0)), (4, (1, 1)), (6, (1, 1)), (10, (2, 1))])
+    }
+
+    #[test]
+    fn interpolates_between_points() {
+        let run = run();
+        assert_eq!(run.position_at(0), Some((1.0, 0.0)));
+        assert_eq!(run.position_at(2), Some((1.0, 0.5)));
+        assert_eq!(run.position_at(4), Some((1.0, 1.0)));
+        assert_eq!(run.position_at(5), Some((1.0, 1.0)));
+        assert_eq!(run.position_at(6), Some((1.0, 1.0)));
+        assert_eq!(run.position_at(9), Some((1.75, 1.0)));
+        assert_eq!(run.position_at(10), Some((2.0, 1.0)));
+    }
+
+    #[test]
+    fn stays_at_the_end() {
+        assert_eq!(run().position_at(500), Some((2.0, 1.0)));
+        assert_eq!(run().ticks(), 10);
+        assert_eq!(Trace::default().position_at(0), None);
+    }
+
+    #[test]
+    fn first_arrival() {
+        let run = run();
+        assert_eq!(run.arrival((1, 1)), Some(4));
+        assert_eq!(run.arrival((2, 1)), Some(10));
+        assert_eq!(run.arrival((5, 5)), None);
+    }
+
+    #[test]
+    fn keeps_the_faster_trace() {
+        let path = env::temp_dir().join(format!("mazing-{}.ghost", std::process::id()));
+        let path = path.to_str().unwrap();
+        let fast = run();
+        let slow = trace(&[(0, (1, 0)), (20, (2, 1))]);
+
+        assert!(fast.save_if_best(path).unwrap());
+        assert!(!slow.save_if_best(path).unwrap());
+        assert_eq!(Trace::load(path).unwrap(), fast);
+
+        // A run on another maze replaces it whatever its time
+        let other = Trace {
+            map_hash: 2,
+            ..slow
+        };
+        assert!(other.save_if_best(path).unwrap());
+        assert_eq!(Trace::load(path).unwrap(), other);
+        fs::remove_file(path).unwrap();
+    }
+}
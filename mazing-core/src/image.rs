@@ -0,0 +1,282 @@
+use crate::cell::Cell;
+use crate::format;
+use crate::map::Map;
+use std::collections::HashSet;
+use std::io;
+
+const WALL: [u8; 3] = [0, 0, 0];
+const PATH: [u8; 3] = [255, 255, 255];
+const START: [u8; 3] = [0, 200, 0];
+const GOAL: [u8; 3] = [220, 0, 0];
+const SOLUTION: [u8; 3] = [255, 220, 0];
+
+// What a pixel of an imported image stands for
+#[derive(Clone, Copy, PartialEq)]
+enum Paint {
+    Wall,
+    Path,
+    Start,
+    Goal,
+}
+
+impl Paint {
+    // Strong green is the start, strong red an exit, and otherwise dark is
+    // wall and light is path. Mostly transparent pixels count as path.
+    fn classify([r, g, b, a]: [u8; 4]) -> Self {
+        if a < 128 {
+            Paint::Path
+        } else if g > 150 && r < 100 && b < 100 {
+            Paint::Start
+        } else if r > 150 && g < 100 && b < 100 {
+            Paint::Goal
+        } else if (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000 < 128 {
+            Paint::Wall
+        } else {
+            Paint::Path
+        }
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Map {
+    // Encode the map as an RGB PNG with `scale` pixels per cell: walls black,
+    // paths white, start green, goals red and the solution (if given) yellow
+    pub fn to_png(&self, scale: usize, solution: Option<&[(usize, usize)]>) -> io::Result<Vec<u8>> {
+        let scale = scale.max(1);
+        let width = self.width * scale;
+        let height = self.height * scale;
+        let solution: HashSet<(usize, usize)> = solution.into_iter().flatten().copied().collect();
+
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for y in 0..self.height {
+            // One row of pixels per cell row, repeated `scale` times
+            let mut row = Vec::with_capacity(width * 3);
+            for x in 0..self.width {
+                let position = (x, y);
+                let color = if position == self.start {
+                    START
+                } else if self.is_goal(position) {
+                    GOAL
+                } else if self.get(x, y) == Some(Cell::Wall) {
+                    WALL
+                } else if solution.contains(&position) {
+                    SOLUTION
+                } else {
+                    PATH
+                };
+                for _ in 0..scale {
+                    row.extend_from_slice(&color);
+                }
+            }
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&pixels).map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)?;
+        Ok(bytes)
+    }
+
+    // Build a map from a PNG painted in the colours `to_png` uses: dark for
+    // walls, light for paths, green for the start and red for exits. Cells
+    // may be painted as square blocks of pixels; the block size is detected.
+    // Without green or red the default entrance and exit are used. The start
+    // and exits must be open and an exit must be reachable.
+    pub fn from_png(bytes: &[u8]) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| invalid_data(format!("not a PNG image: {}", e)))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|e| invalid_data(format!("could not decode PNG: {}", e)))?;
+
+        let (image_width, image_height) = (info.width as usize, info.height as usize);
+        let channels = info.color_type.samples();
+        let paint_at = |x: usize, y: usize| {
+            let offset = y * info.line_size + x * channels;
+            let pixel = &buffer[offset..offset + channels];
+            Paint::classify(match channels {
+                1 => [pixel[0], pixel[0], pixel[0], 255],
+                2 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                3 => [pixel[0], pixel[1], pixel[2], 255],
+                _ => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            })
+        };
+
+        // Largest block size that divides the image into single-colour cells
+        let scale = (1..=gcd(image_width, image_height))
+            .rev()
+            .filter(|scale| image_width % scale == 0 && image_height % scale == 0)
+            .find(|&scale| {
+                (0..image_height).all(|y| {
+                    (0..image_width)
+                        .all(|x| paint_at(x, y) == paint_at(x - x % scale, y - y % scale))
+                })
+            })
+            .unwrap_or(1);
+        let width = image_width / scale;
+        let height = image_height / scale;
+        format::check_dimensions(width, height)?;
+
+        let mut grid = Vec::with_capacity(width * height);
+        let mut start = None;
+        let mut goals = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                let paint = paint_at(x * scale, y * scale);
+                match paint {
+                    Paint::Start => {
+                        if let Some((sx, sy)) = start {
+                            return Err(invalid_data(format!(
+                                "cells ({}, {}) and ({}, {}) are both marked as the start",
+                                sx, sy, x, y
+                            )));
+                        }
+                        start = Some((x, y));
+                    }
+                    Paint::Goal => goals.push((x, y)),
+                    Paint::Wall | Paint::Path => {}
+                }
+                grid.push(if paint == Paint::Wall {
+                    Cell::Wall
+                } else {
+                    Cell::Path
+                });
+            }
+        }
+
+        // Fall back to the usual entrance and exit, which must then be open
+        let start = start.unwrap_or((1, 0));
+        if goals.is_empty() {
+            goals.push((width - 2, height - 1));
+        }
+        for &(x, y) in std::iter::once(&start).chain(&goals) {
+            if grid[y * width + x] == Cell::Wall {
+                let role = if (x, y) == start { "start" } else { "exit" };
+                return Err(invalid_data(format!(
+                    "the {} at cell ({}, {}) is a wall",
+                    role, x, y
+                )));
+            }
+        }
+
+        let map = Map::from_grid(width, height, grid, start, goals)?;
+        if map.solve().is_none() {
+            return Err(invalid_data(
+                "no exit can be reached from the start".to_string(),
+            ));
+        }
+        Ok(map)
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{error, maze};
+    use crate::generators::Algorithm;
+
+    // An RGB image of a maze drawn as text, `scale` pixels per cell:
+    // `#` wall, `S` start, `E` exit and anything else path
+    fn paint(rows: &[&str], scale: usize) -> Vec<u8> {
+        let (width, height) = (rows[0].len() * scale, rows.len() * scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for row in rows {
+            let mut line = vec![];
+            for symbol in row.chars() {
+                let color = match symbol {
+                    '#' => WALL,
+                    'S' => START,
+                    'E' => GOAL,
+                    _ => PATH,
+                };
+                for _ in 0..scale {
+                    line.extend_from_slice(&color);
+                }
+            }
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&pixels).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let map = maze(15, 11, Algorithm::Kruskal, 3);
+        let loaded = Map::from_png(&map.to_png(1, None).unwrap()).unwrap();
+        assert_eq!((loaded.width, loaded.height), (15, 11));
+        assert_eq!(loaded.content_hash(), map.content_hash());
+    }
+
+    #[test]
+    fn round_trip_scaled_with_solution() {
+        let map = maze(15, 11, Algorithm::Kruskal, 3);
+        let solution = map.solve().unwrap();
+        let bytes = map.to_png(4, Some(&solution)).unwrap();
+        let loaded = Map::from_png(&bytes).unwrap();
+        assert_eq!((loaded.width, loaded.height), (15, 11));
+        assert_eq!(loaded.content_hash(), map.content_hash());
+    }
+
+    #[test]
+    fn detects_block_size() {
+        // 45x27 pixels: 9 divides both, but only blocks of 3 are one colour
+        let map = maze(15, 9, Algorithm::Prim, 8);
+        let loaded = Map::from_png(&map.to_png(3, None).unwrap()).unwrap();
+        assert_eq!((loaded.width, loaded.height), (15, 9));
+        assert_eq!(loaded.content_hash(), map.content_hash());
+    }
+
+    #[test]
+    fn painted_start_and_exit() {
+        let map = Map::from_png(&paint(&["#####", "S   #", "###E#"], 2)).unwrap();
+        assert_eq!(map.start, (0, 1));
+        assert_eq!(map.goals, vec![(3, 2)]);
+    }
+
+    #[test]
+    fn no_start_falls_back_to_a_wall() {
+        let e = error(Map::from_png(&paint(&["#####", "#   #", "###E#"], 1)));
+        assert!(e.to_string().contains("start at cell (1, 0) is a wall"));
+    }
+
+    #[test]
+    fn no_exit_falls_back_to_a_wall() {
+        let e = error(Map::from_png(&paint(&["#S###", "#   #", "#####"], 1)));
+        assert!(e.to_string().contains("exit at cell (3, 2) is a wall"));
+    }
+
+    #[test]
+    fn two_starts() {
+        let e = error(Map::from_png(&paint(&["#S###", "#   S", "###E#"], 1)));
+        assert!(e.to_string().contains("both marked as the start"));
+    }
+}
@@ -0,0 +1,171 @@
+// Best runs on each map, kept in one small file per map so a team sharing
+// a maps directory shares its leaderboards. Files are named after the map's
+// content hash, so renamed or copied maps keep their scores.
+
+use crate::map::Map;
+use crate::session::Session;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Runs kept on each leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// One finished run
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub name: String,
+    pub time: f32,    // Seconds from the first move to reaching the exit
+    pub moves: usize, // Steps taken
+    pub date: u64,    // Unix time in seconds
+}
+
+impl Score {
+    // The score for a session's run, None if the solution was shown during
+    // it, since such runs are never ranked
+    pub fn of_run(session: &Session, name: &str, date: u64) -> Option<Self> {
+        if session.assisted {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            time: session.elapsed,
+            moves: session.moves,
+            date,
+        })
+    }
+}
+
+// A map's best runs, fastest first, ties going to fewer moves
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub scores: Vec<Score>,
+}
+
+impl Leaderboard {
+    // Read the leaderboard for a map from `dir`; empty if it has none yet
+    pub fn load(dir: &str, map: &Map) -> Result<Self, io::Error> {
+        let bytes = match fs::read(Self::path(dir, map)) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        bincode::deserialize(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // Write the leaderboard for a map to `dir`, creating it if needed
+    pub fn save(&self, dir: &str, map: &Map) -> Result<(), io::Error> {
+        fs::create_dir_all(dir)?;
+        let bytes = bincode::serialize(self).map_err(io::Error::other)?;
+        fs::write(Self::path(dir, map), bytes)
+    }
+
+    // Add a run in its place. Returns its rank counting from 0, or None if
+    // it was not good enough to be kept.
+    pub fn submit(&mut self, score: Score) -> Option<usize> {
+        let rank = self
+            .scores
+            .iter()
+            .position(|other| (score.time, score.moves) < (other.time, other.moves))
+            .unwrap_or(self.scores.len());
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.scores.insert(rank, score);
+        self.scores.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+
+    fn path(dir: &str, map: &Map) -> String {
+        Path::new(dir)
+            .join(format!("{:016x}.scores", map.content_hash()))
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::maze;
+    use crate::generators::Algorithm;
+    use std::env;
+
+    fn score(name: &str, time: f32, moves: usize) -> Score {
+        Score {
+            name: name.to_string(),
+            time,
+            moves,
+            date: 0,
+        }
+    }
+
+    fn names(leaderboard: &Leaderboard) -> Vec<&str> {
+        leaderboard.scores.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn fastest_first_then_fewest_moves() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(leaderboard.submit(score("slow", 20.0, 30)), Some(0));
+        assert_eq!(leaderboard.submit(score("fast", 10.0, 40)), Some(0));
+        assert_eq!(leaderboard.submit(score("fewer", 10.0, 30)), Some(0));
+        // A tie on both goes behind the run already there
+        assert_eq!(leaderboard.submit(score("tied", 10.0, 30)), Some(1));
+        assert_eq!(names(&leaderboard), ["fewer", "tied", "fast", "slow"]);
+    }
+
+    #[test]
+    fn keeps_only_the_best() {
+        let mut leaderboard = Leaderboard::default();
+        for i in 0..LEADERBOARD_SIZE {
+            leaderboard.submit(score(&i.to_string(), 10.0 + i as f32, 5));
+        }
+        assert_eq!(leaderboard.submit(score("last", 99.0, 5)), None);
+        assert_eq!(leaderboard.submit(score("best", 1.0, 5)), Some(0));
+        assert_eq!(leaderboard.scores.len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard.scores[0].name, "best");
+        assert_eq!(leaderboard.scores[LEADERBOARD_SIZE - 1].name, "8");
+    }
+
+    #[test]
+    fn assisted_runs_are_not_scored() {
+        let mut session = Session::new(maze(11, 11, Algorithm::Prim, 1));
+        session.elapsed = 12.5;
+        session.moves = 30;
+        assert_eq!(
+            Score::of_run(&session, "ann", 7),
+            Some(Score {
+                name: "ann".to_string(),
+                time: 12.5,
+                moves: 30,
+                date: 7,
+            })
+        );
+        session.assisted = true;
+        assert_eq!(Score::of_run(&session, "ann", 7), None);
+    }
+
+    #[test]
+    fn boards_are_kept_per_maze() {
+        let dir = env::temp_dir().join(format!("mazing-scores-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let map = maze(11, 11, Algorithm::Prim, 1);
+        let other = maze(11, 11, Algorithm::Prim, 2);
+
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.submit(score("ann", 10.0, 20));
+        leaderboard.save(dir, &map).unwrap();
+
+        // The same maze under another name shares the board
+        let mut renamed = maze(11, 11, Algorithm::Prim, 1);
+        renamed.metadata.name = "renamed".to_string();
+        assert_eq!(Leaderboard::load(dir, &renamed).unwrap(), leaderboard);
+        assert_eq!(
+            Leaderboard::load(dir, &other).unwrap(),
+            Leaderboard::default()
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
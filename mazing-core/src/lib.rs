@@ -0,0 +1,43 @@
+// Maze simulation shared by the game and the command-line tools: maps,
+// generators, solvers, exporters and the rules of play. Has no graphics
+// dependency so it builds anywhere.
+
+pub mod analysis;
+mod ascii;
+pub mod book;
+pub mod cell;
+pub mod difficulty;
+#[cfg(test)]
+mod fixtures;
+pub mod format;
+pub mod generators;
+pub mod ghost;
+mod image;
+pub mod leaderboard;
+pub mod map;
+pub mod metadata;
+pub mod pdf;
+pub mod player;
+pub mod replay;
+mod segments;
+pub mod session;
+pub mod solvers;
+mod svg;
+mod visibility;
+
+pub use analysis::MazeMetrics;
+pub use ascii::AsciiError;
+pub use book::BookOptions;
+pub use cell::Cell;
+pub use difficulty::{Difficulty, DifficultyRequest};
+pub use generators::{Algorithm, MazeGenerator};
+pub use ghost::{Ghost, Trace};
+pub use leaderboard::{Leaderboard, Score};
+pub use map::{CarveEvent, Map, MazeRng};
+pub use metadata::Metadata;
+pub use player::Player;
+pub use replay::{Recording, Replay};
+pub use segments::WallSegment;
+pub use session::{Direction, Session};
+pub use solvers::{SolveResult, Solver, SolverKind};
+pub use svg::SvgOptions;
@@ -0,0 +1,414 @@
+use crate::cell::Cell;
+use crate::difficulty::difficulty_score;
+use crate::format;
+use crate::generators::{Algorithm, MazeGenerator};
+use crate::metadata::Metadata;
+use crate::solvers::{Bfs, Solver};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+// RNG used for maze generation. ChaCha8 is portable across platforms and
+// rand releases, so a seed always reproduces the same maze.
+pub type MazeRng = ChaCha8Rng;
+
+// A single cell change made while generating, for animating generation
+#[derive(Clone, Copy, PartialEq)]
+pub struct CarveEvent {
+    pub x: usize,
+    pub y: usize,
+    pub cell: Cell,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Map {
+    pub width: usize,
+    pub height: usize,
+    pub grid: Vec<Cell>,
+    pub metadata: Metadata,
+    pub start: (usize, usize),      // Where the player enters the maze
+    pub goals: Vec<(usize, usize)>, // Reaching any of these wins
+    #[serde(skip)]
+    history: Option<Vec<CarveEvent>>, // Cell changes, while recording
+}
+
+impl Map {
+    // Creates a new map filled with walls
+    pub fn new(width: usize, height: usize) -> Self {
+        // Ensure the width and height are odd numbers
+        let width = if width % 2 == 1 { width } else { width - 1 };
+        let height = if height % 2 == 1 { height } else { height - 1 };
+
+        Self {
+            width,
+            height,
+            grid: vec![Cell::Wall; width * height],
+            metadata: Metadata::new(),
+            // Entrance at the top left, exit at the bottom right
+            start: (1, 0),
+            goals: vec![(width - 2, height - 1)],
+            history: None,
+        }
+    }
+
+    // Build a map around an existing grid, e.g. one drawn by hand. Unlike
+    // `new` the size is kept as given; generation metadata is left empty.
+    // Fails on maps the file format would refuse to load, so anything
+    // built here can be saved and read back.
+    pub fn from_grid(
+        width: usize,
+        height: usize,
+        grid: Vec<Cell>,
+        start: (usize, usize),
+        goals: Vec<(usize, usize)>,
+    ) -> io::Result<Self> {
+        let map = Self {
+            width,
+            height,
+            grid,
+            metadata: Metadata::default(),
+            start,
+            goals,
+            history: None,
+        };
+        let mut map = format::check(map)?;
+        map.metadata.difficulty = map.difficulty();
+        Ok(map)
+    }
+
+    // Access cell in the grid with boundary checking
+    pub fn get(&self, x: usize, y: usize) -> Option<Cell> {
+        if x < self.width && y < self.height {
+            Some(self.grid[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    // Set a cell in the grid
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+            if let Some(history) = self.history.as_mut() {
+                if self.grid[index] != cell {
+                    history.push(CarveEvent { x, y, cell });
+                }
+            }
+            self.grid[index] = cell;
+        }
+    }
+
+    // Start recording every cell change, e.g. before generating. Replaying
+    // the recording onto a map full of walls steps through the generation.
+    // Generators carve straight into the map in plain loops; recording their
+    // changes here gives every one of them step-by-step events without
+    // rewriting each as a resumable state machine.
+    pub fn start_recording(&mut self) {
+        self.history = Some(Vec::new());
+    }
+
+    // Stop recording and return the changes in the order they happened
+    pub fn take_recording(&mut self) -> Vec<CarveEvent> {
+        self.history.take().unwrap_or_default()
+    }
+
+    // Generate a maze from a seed; the same algorithm and seed always yield
+    // the same maze
+    pub fn generate_maze(&mut self, algorithm: Algorithm, seed: u64) {
+        let mut rng = MazeRng::seed_from_u64(seed);
+        self.metadata.seed = seed;
+        self.metadata.algorithm = Some(algorithm);
+        self.generate_maze_with_rng(algorithm.generator().as_ref(), &mut rng);
+    }
+
+    // Generate a maze with the given generator, drawing from the given RNG
+    pub fn generate_maze_with_rng(&mut self, generator: &dyn MazeGenerator, rng: &mut MazeRng) {
+        // Start from a clean grid so regenerating is deterministic
+        self.grid.fill(Cell::Wall);
+        generator.generate(self, rng);
+
+        // Open the entrance and exits
+        self.set(self.start.0, self.start.1, Cell::Path);
+        for (x, y) in self.goals.clone() {
+            self.set(x, y, Cell::Path);
+        }
+        self.metadata.difficulty = self.difficulty();
+    }
+
+    // Whether a position is one of the map's goals
+    pub fn is_goal(&self, position: (usize, usize)) -> bool {
+        self.goals.contains(&position)
+    }
+
+    // FNV-1a hash of the maze itself: size, which cells are open, start and
+    // exits. Metadata and legacy solution markers don't change it.
+    pub fn content_hash(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut hash = OFFSET;
+        let mut feed = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash = (hash ^ byte as u64).wrapping_mul(PRIME);
+            }
+        };
+
+        let mut positions = vec![(self.width, self.height), self.start];
+        positions.extend(&self.goals);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                feed(&[self.move_cost(x, y).is_some() as u8]);
+            }
+        }
+        for (x, y) in positions {
+            feed(&(x as u64).to_le_bytes());
+            feed(&(y as u64).to_le_bytes());
+        }
+        hash
+    }
+
+    // Remove a fraction (0.0 to 1.0) of the dead ends by knocking out a wall
+    // next to each, creating loops. Uses its own RNG stream derived from the
+    // map's seed, so braiding a regenerated maze gives the same result.
+    pub fn braid(&mut self, ratio: f32) {
+        let mut rng = MazeRng::seed_from_u64(self.metadata.seed);
+        rng.set_stream(1);
+        self.metadata.braid = ratio;
+
+        let mut dead_ends = self.dead_ends();
+        dead_ends.shuffle(&mut rng);
+        let mut to_remove = (dead_ends.len() as f32 * ratio.clamp(0.0, 1.0)).round() as usize;
+
+        for (x, y) in dead_ends {
+            if to_remove == 0 {
+                break;
+            }
+            // An earlier removal may already have opened this one up
+            if !self.is_dead_end(x, y) {
+                continue;
+            }
+
+            // Walled-off neighbouring cells, preferring other dead ends so a
+            // single removal fixes two of them
+            let candidates: Vec<(usize, usize)> = [(-2isize, 0), (2, 0), (0, -2), (0, 2)]
+                .iter()
+                .map(|&(dx, dy)| (x.wrapping_add(dx as usize), y.wrapping_add(dy as usize)))
+                .filter(|&(nx, ny)| {
+                    nx < self.width
+                        && ny < self.height
+                        && self.get((x + nx) / 2, (y + ny) / 2) == Some(Cell::Wall)
+                })
+                .collect();
+            let paired: Vec<(usize, usize)> = candidates
+                .iter()
+                .copied()
+                .filter(|&(nx, ny)| self.is_dead_end(nx, ny))
+                .collect();
+            let pool = if paired.is_empty() {
+                candidates
+            } else {
+                paired
+            };
+
+            if let Some(&(nx, ny)) = pool.choose(&mut rng) {
+                self.set((x + nx) / 2, (y + ny) / 2, Cell::Path);
+                to_remove -= 1;
+            }
+        }
+        self.metadata.difficulty = self.difficulty();
+    }
+
+    // Cells on the odd lattice with a single open side
+    fn dead_ends(&self) -> Vec<(usize, usize)> {
+        let mut dead_ends = vec![];
+        for y in (1..self.height).step_by(2) {
+            for x in (1..self.width).step_by(2) {
+                if self.is_dead_end(x, y) {
+                    dead_ends.push((x, y));
+                }
+            }
+        }
+        dead_ends
+    }
+
+    // Whether an open cell has exactly one open neighbour
+    fn is_dead_end(&self, x: usize, y: usize) -> bool {
+        if self.get(x, y) == Some(Cell::Wall) {
+            return false;
+        }
+        let open_sides = [(0isize, 1), (1, 0), (0, -1), (-1, 0)]
+            .iter()
+            .filter(|&&(dx, dy)| {
+                let nx = x.wrapping_add(dx as usize);
+                let ny = y.wrapping_add(dy as usize);
+                matches!(self.get(nx, ny), Some(Cell::Path) | Some(Cell::Solution))
+            })
+            .count();
+        open_sides == 1
+    }
+
+    // Cost of stepping onto a cell, or None if it cannot be entered. Every
+    // open cell costs the same for now; weighted solvers read costs from here.
+    pub fn move_cost(&self, x: usize, y: usize) -> Option<u32> {
+        match self.get(x, y)? {
+            Cell::Wall => None,
+            Cell::Path | Cell::Solution => Some(1),
+        }
+    }
+
+    // Shortest path from the start to the nearest goal (both included), or
+    // None if no goal is reachable. The grid is left untouched; see
+    // `solvers` for other algorithms.
+    pub fn solve(&self) -> Option<Vec<(usize, usize)>> {
+        Bfs.solve(self).path
+    }
+
+    // Difficulty score of the maze (see `difficulty_score`), None if
+    // unsolvable
+    pub fn difficulty(&self) -> Option<f32> {
+        difficulty_score(&self.metrics())
+    }
+
+    // Save the map to a file in the current format (see `format`), or as
+    // ASCII text or a one-pixel-per-cell image if the name ends in `.txt` or
+    // `.png`
+    pub fn save_to_file(&self, filename: &str) -> Result<(), io::Error> {
+        if filename.ends_with(".txt") {
+            return fs::write(filename, self.to_ascii(None));
+        }
+        if filename.ends_with(".png") {
+            return fs::write(filename, self.to_png(1, None)?);
+        }
+        let bytes = format::encode(self)?;
+        let mut writer = BufWriter::new(File::create(filename)?);
+        writer.write_all(&bytes)?;
+        writer.flush()
+    }
+
+    // Load a map from a file, accepting older formats, `.txt` maps drawn in
+    // the ASCII format (see `from_ascii`) and `.png` images (see `from_png`)
+    pub fn load_from_file(filename: &str) -> Result<Self, io::Error> {
+        if filename.ends_with(".txt") {
+            let text = fs::read_to_string(filename)?;
+            return Ok(Self::from_ascii(&text)?);
+        }
+        if filename.ends_with(".png") {
+            return Self::from_png(&fs::read(filename)?);
+        }
+        let mut bytes = Vec::new();
+        BufReader::new(File::open(filename)?).read_to_end(&mut bytes)?;
+        format::decode(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::maze;
+
+    // Hash of the maze each algorithm carves from seed 42. A change here
+    // means saved seeds no longer reproduce the same mazes.
+    fn generated(algorithm: Algorithm) -> u64 {
+        maze(21, 15, algorithm, 42).content_hash()
+    }
+
+    #[test]
+    fn recording_replays_generation_and_braiding() {
+        for algorithm in Algorithm::ALL {
+            let mut map = Map::new(21, 15);
+            map.start_recording();
+            map.generate_maze(algorithm, 42);
+            map.braid(0.5);
+            let mut replayed = Map::new(21, 15);
+            for event in map.take_recording() {
+                replayed.set(event.x, event.y, event.cell);
+            }
+            assert_eq!(replayed.content_hash(), map.content_hash());
+            assert!(map.take_recording().is_empty());
+        }
+    }
+
+    #[test]
+    fn recursive_backtracker_is_stable() {
+        assert_eq!(
+            generated(Algorithm::RecursiveBacktracker),
+            0x4160_2368_2d0f_43c8
+        );
+    }
+
+    #[test]
+    fn prim_is_stable() {
+        assert_eq!(generated(Algorithm::Prim), 0x054c_0500_2b6f_a9ec);
+    }
+
+    #[test]
+    fn kruskal_is_stable() {
+        assert_eq!(generated(Algorithm::Kruskal), 0xfc8a_1c0f_7783_1fb0);
+    }
+
+    #[test]
+    fn wilson_is_stable() {
+        assert_eq!(generated(Algorithm::Wilson), 0x995b_5cf7_23b6_2e14);
+    }
+
+    #[test]
+    fn aldous_broder_is_stable() {
+        assert_eq!(generated(Algorithm::AldousBroder), 0x024a_945c_988c_f8e4);
+    }
+
+    #[test]
+    fn eller_is_stable() {
+        assert_eq!(generated(Algorithm::Eller), 0xd1ab_1f33_0117_0ad8);
+    }
+
+    #[test]
+    fn hunt_and_kill_is_stable() {
+        assert_eq!(generated(Algorithm::HuntAndKill), 0x34c1_be5b_9f2a_0730);
+    }
+
+    #[test]
+    fn binary_tree_is_stable() {
+        assert_eq!(generated(Algorithm::BinaryTree), 0x84ba_e903_91de_3ae0);
+    }
+
+    #[test]
+    fn sidewinder_is_stable() {
+        assert_eq!(generated(Algorithm::Sidewinder), 0xe818_ee3b_0aad_dd3c);
+    }
+
+    #[test]
+    fn growing_tree_is_stable() {
+        assert_eq!(generated(Algorithm::GrowingTree), 0x9d1c_1d1d_b560_7650);
+    }
+
+    #[test]
+    fn recursive_division_is_stable() {
+        assert_eq!(
+            generated(Algorithm::RecursiveDivision {
+                horizontal_bias: 50
+            }),
+            0x631f_bcf0_20bd_87cc
+        );
+    }
+
+    #[test]
+    fn recursive_division_horizontal_is_stable() {
+        assert_eq!(
+            generated(Algorithm::RecursiveDivision {
+                horizontal_bias: 80
+            }),
+            0x2d60_e9c6_53c4_07d4
+        );
+    }
+
+    #[test]
+    fn recursive_division_vertical_is_stable() {
+        assert_eq!(
+            generated(Algorithm::RecursiveDivision {
+                horizontal_bias: 20
+            }),
+            0x34ea_7602_8398_8158
+        );
+    }
+}
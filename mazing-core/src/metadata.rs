@@ -0,0 +1,63 @@
+use crate::generators::Algorithm;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Descriptive information saved alongside a map's grid
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    pub name: String,                 // Display name, empty if never named
+    pub author: String,               // Who made the map, empty if unknown
+    pub created: u64,                 // Unix time in seconds, 0 if unknown
+    pub algorithm: Option<Algorithm>, // Generator used, None for hand-made maps
+    pub seed: u64,                    // Seed the maze was generated from
+    pub braid: f32,                   // Fraction of dead ends removed after generation
+    pub difficulty: Option<f32>,      // Computed rating, None if unsolvable
+    pub tags: Vec<String>,
+}
+
+impl Metadata {
+    // Metadata for a map created now by the current user
+    pub fn new() -> Self {
+        Self {
+            author: current_user(),
+            created: now(),
+            ..Self::default()
+        }
+    }
+}
+
+// Current Unix time in seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+// Login name of the current user, or an empty string
+pub fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+// Format a Unix timestamp as a UTC date, e.g. "2024-03-09"
+pub fn format_date(timestamp: u64) -> String {
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,173 @@
+// Just enough of PDF 1.4 to write pages of vector drawings and text in the
+// built-in Helvetica font, without pulling in a PDF library.
+
+use std::fmt::Write;
+
+// Size of an A4 page in points
+pub const PAGE_WIDTH: f32 = 595.0;
+pub const PAGE_HEIGHT: f32 = 842.0;
+
+// A point on the page, in points
+pub type Point = (f32, f32);
+
+// A document being assembled page by page
+#[derive(Default)]
+pub struct Pdf {
+    pages: Vec<String>, // Content stream of each page
+}
+
+impl Pdf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Append a page drawn by `content`
+    pub fn add_page(&mut self, content: Page) {
+        self.pages.push(content.stream);
+    }
+
+    // Serialize the document. Objects are numbered: 1 catalog, 2 page tree,
+    // 3 font, then a page object and its content stream for each page.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = vec![];
+        let mut object = |out: &mut String, body: String| {
+            offsets.push(out.len());
+            let _ = write!(out, "{} 0 obj\n{}\nendobj\n", offsets.len(), body);
+        };
+
+        let kids: Vec<String> = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", 4 + i * 2))
+            .collect();
+        object(&mut out, "<< /Type /Catalog /Pages 2 0 R >>".to_string());
+        object(
+            &mut out,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            ),
+        );
+        object(
+            &mut out,
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        );
+        for (i, stream) in self.pages.iter().enumerate() {
+            object(
+                &mut out,
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    5 + i * 2
+                ),
+            );
+            object(
+                &mut out,
+                format!(
+                    "<< /Length {} >>\nstream\n{}endstream",
+                    stream.len(),
+                    stream
+                ),
+            );
+        }
+
+        let xref = out.len();
+        let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in &offsets {
+            let _ = writeln!(out, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            offsets.len() + 1,
+            xref
+        );
+        out.into_bytes()
+    }
+}
+
+// Drawing commands for one page. Coordinates are in points from the top
+// left, like the rest of the code base; they are flipped for PDF on output.
+#[derive(Default)]
+pub struct Page {
+    stream: String,
+}
+
+impl Page {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Draw text with its baseline starting at (x, y)
+    pub fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        // Only printable ASCII is safe in the standard font encoding
+        let escaped: String = text
+            .chars()
+            .map(|c| {
+                if c.is_ascii() && !c.is_ascii_control() {
+                    c
+                } else {
+                    '?'
+                }
+            })
+            .collect::<String>()
+            .replace('\\', "\\\\")
+            .replace('(', "\\(")
+            .replace(')', "\\)");
+        let _ = writeln!(
+            self.stream,
+            "BT /F1 {} Tf {} {} Td ({}) Tj ET",
+            size,
+            x,
+            PAGE_HEIGHT - y,
+            escaped
+        );
+    }
+
+    // Stroke a set of straight lines in one colour. `square_caps` extends
+    // each end by half the width, so touching lines join without gaps.
+    pub fn lines(
+        &mut self,
+        lines: &[(Point, Point)],
+        width: f32,
+        color: [f32; 3],
+        square_caps: bool,
+    ) {
+        self.set_stroke(width, color, if square_caps { 2 } else { 1 });
+        for &((x1, y1), (x2, y2)) in lines {
+            let _ = writeln!(
+                self.stream,
+                "{} {} m {} {} l",
+                x1,
+                PAGE_HEIGHT - y1,
+                x2,
+                PAGE_HEIGHT - y2
+            );
+        }
+        self.stream.push_str("S\n");
+    }
+
+    // Stroke a connected line through the given points
+    pub fn polyline(&mut self, points: &[Point], width: f32, color: [f32; 3]) {
+        let Some(&(x, y)) = points.first() else {
+            return;
+        };
+        self.set_stroke(width, color, 1);
+        let _ = writeln!(self.stream, "{} {} m", x, PAGE_HEIGHT - y);
+        for &(x, y) in &points[1..] {
+            let _ = writeln!(self.stream, "{} {} l", x, PAGE_HEIGHT - y);
+        }
+        self.stream.push_str("S\n");
+    }
+
+    // Line width, RGB colour (0 to 1) and cap style (1 round, 2 square)
+    fn set_stroke(&mut self, width: f32, [r, g, b]: [f32; 3], cap: u8) {
+        let _ = writeln!(
+            self.stream,
+            "{} w {} J 1 j {} {} {} RG",
+            width, cap, r, g, b
+        );
+    }
+}
@@ -0,0 +1,182 @@
+// Recorded runs. A session steps in fixed ticks, so the directions held at
+// each tick are enough to play a run back exactly.
+
+use crate::map::Map;
+use crate::session::{Direction, Session, MAX_FRAME_TIME, TICK};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+
+// A change in the direction held, from `tick` on
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Input {
+    pub tick: u64,
+    pub direction: Option<Direction>, // None when no direction is held
+}
+
+// The inputs of one run on a map
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub map_hash: u64, // `Map::content_hash` of the map it was made on
+    pub inputs: Vec<Input>,
+    pub ticks: u64, // Length of the run
+}
+
+impl Recording {
+    // File a recording of the map at `map_path` is kept in, beside the map
+    pub fn path_for(map_path: &str) -> String {
+        format!("{}.replay", map_path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), io::Error> {
+        let bytes = bincode::serialize(self).map_err(io::Error::other)?;
+        fs::write(path, bytes)
+    }
+
+    pub fn load(path: &str) -> Result<Self, io::Error> {
+        let bytes = fs::read(path)?;
+        bincode::deserialize(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // Run time in seconds
+    pub fn duration(&self) -> f32 {
+        self.ticks as f32 * TICK
+    }
+}
+
+// Plays a recording back through a session, feeding each input at the tick
+// it was recorded on
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    recording: Recording,
+    next: usize,      // Index of the next input to feed
+    accumulator: f32, // Frame time not yet simulated
+}
+
+impl Replay {
+    // Fails if the recording was made on a different maze
+    pub fn new(recording: Recording, map: &Map) -> Result<Self, io::Error> {
+        if recording.map_hash != map.content_hash() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the recording was made on a different maze",
+            ));
+        }
+        Ok(Self {
+            recording,
+            next: 0,
+            accumulator: 0.0,
+        })
+    }
+
+    // Simulate one tick of the session
+    pub fn step(&mut self, session: &mut Session) {
+        if self.is_finished(session) {
+            return;
+        }
+        while let Some(input) = self.recording.inputs.get(self.next) {
+            if input.tick > session.ticks {
+                break;
+            }
+            session.set_input(input.direction);
+            self.next += 1;
+        }
+        session.tick();
+    }
+
+    // Advance by `delta_time` seconds of real time, catching up on no more
+    // than `MAX_FRAME_TIME` at once, as a live session does
+    pub fn update(&mut self, session: &mut Session, delta_time: f32) {
+        self.accumulator = (self.accumulator + delta_time).min(MAX_FRAME_TIME);
+        while self.accumulator >= TICK {
+            self.accumulator -= TICK;
+            self.step(session);
+        }
+    }
+
+    // Play the rest of the recording at once
+    pub fn finish(&mut self, session: &mut Session) {
+        while !self.is_finished(session) {
+            self.step(session);
+        }
+    }
+
+    // Whether the session has reached the end of the recording
+    pub fn is_finished(&self, session: &Session) -> bool {
+        session.ticks >= self.recording.ticks
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{braided, maze};
+    use crate::generators::Algorithm;
+
+    // The maze every run here is played on
+    fn track() -> Map {
+        braided(15, 11, Algorithm::RecursiveBacktracker, 11, 0.3)
+    }
+
+    #[test]
+    fn replay_matches_the_recorded_run() {
+        // Wander with uneven frame times, turning and letting go now and then
+        let directions = [
+            Some(Direction::Down),
+            Some(Direction::Right),
+            None,
+            Some(Direction::Up),
+            Some(Direction::Right),
+            Some(Direction::Left),
+            Some(Direction::Down),
+        ];
+        let deltas = [0.016, 0.033, 0.007, 0.021, 0.05, 0.0, 0.3, 0.012];
+        let mut live = Session::new(track());
+        for frame in 0..600 {
+            if frame % 9 == 0 {
+                live.set_input(directions[frame / 9 % directions.len()]);
+            }
+            live.update(deltas[frame % deltas.len()]);
+        }
+        assert!(live.moves > 0 && live.backtracks > 0);
+
+        let mut replayed = Session::new(track());
+        let mut replay = Replay::new(live.recording(), &replayed.map).unwrap();
+        replay.finish(&mut replayed);
+
+        assert_eq!(replayed.ticks, live.ticks);
+        assert_eq!(replayed.player.position, live.player.position);
+        assert_eq!(replayed.elapsed.to_bits(), live.elapsed.to_bits());
+        assert_eq!(replayed.moves, live.moves);
+        assert_eq!(replayed.backtracks, live.backtracks);
+        assert_eq!(replayed.trace(), live.trace());
+    }
+
+    #[test]
+    fn replay_rejects_another_maze() {
+        let other = maze(15, 11, Algorithm::RecursiveBacktracker, 12);
+        let recording = Session::new(track()).recording();
+        assert!(Replay::new(recording, &other).is_err());
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        // A stalled frame replays no more ticks than it plays live
+        let mut live = Session::new(track());
+        live.update(10.0);
+        let clamped = live.ticks;
+        assert!(clamped > 0 && clamped as f32 * TICK <= MAX_FRAME_TIME);
+        for _ in 0..100 {
+            live.tick();
+        }
+
+        let mut session = Session::new(track());
+        let mut replay = Replay::new(live.recording(), &session.map).unwrap();
+        replay.update(&mut session, 10.0);
+        assert_eq!(session.ticks, clamped);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::cell::Cell;
+use crate::map::Map;
+
+// A straight run of wall cells, from one cell centre to another in grid
+// coordinates. Both ends are equal for a wall cell standing on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WallSegment {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+}
+
+impl Map {
+    // The walls as line segments through the centres of wall cells, with each
+    // horizontal and vertical run merged into one segment. Vector exporters
+    // stroke these instead of filling one square per cell.
+    pub fn wall_segments(&self) -> Vec<WallSegment> {
+        let is_wall = |x: usize, y: usize| self.get(x, y) == Some(Cell::Wall);
+        let mut segments = vec![];
+
+        // Horizontal runs of two or more cells
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                if !is_wall(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let run_start = x;
+                while x + 1 < self.width && is_wall(x + 1, y) {
+                    x += 1;
+                }
+                if x > run_start {
+                    segments.push(WallSegment {
+                        from: (run_start, y),
+                        to: (x, y),
+                    });
+                }
+                x += 1;
+            }
+        }
+
+        // Vertical runs of two or more cells
+        for x in 0..self.width {
+            let mut y = 0;
+            while y < self.height {
+                if !is_wall(x, y) {
+                    y += 1;
+                    continue;
+                }
+                let run_start = y;
+                while y + 1 < self.height && is_wall(x, y + 1) {
+                    y += 1;
+                }
+                if y > run_start {
+                    segments.push(WallSegment {
+                        from: (x, run_start),
+                        to: (x, y),
+                    });
+                }
+                y += 1;
+            }
+        }
+
+        // Wall cells with no wall neighbours, which no run covers
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let isolated = is_wall(x, y)
+                    && ![(0isize, 1), (1, 0), (0, -1), (-1, 0)]
+                        .iter()
+                        .any(|&(dx, dy)| {
+                            is_wall(x.wrapping_add(dx as usize), y.wrapping_add(dy as usize))
+                        });
+                if isolated {
+                    segments.push(WallSegment {
+                        from: (x, y),
+                        to: (x, y),
+                    });
+                }
+            }
+        }
+
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::from_rows;
+
+    fn segment(from: (usize, usize), to: (usize, usize)) -> WallSegment {
+        WallSegment { from, to }
+    }
+
+    #[test]
+    fn runs_are_merged() {
+        let map = from_rows(&[
+            "#####", //
+            "#   #", //
+            "# # #", //
+            "#   #", //
+            "#####",
+        ]);
+        assert_eq!(
+            map.wall_segments(),
+            vec![
+                segment((0, 0), (4, 0)),
+                segment((0, 4), (4, 4)),
+                segment((0, 0), (0, 4)),
+                segment((4, 0), (4, 4)),
+                segment((2, 2), (2, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn corners_join_two_runs() {
+        let map = from_rows(&[
+            "##  ", //
+            "#   ", //
+            "#  #",
+        ]);
+        assert_eq!(
+            map.wall_segments(),
+            vec![
+                segment((0, 0), (1, 0)),
+                segment((0, 0), (0, 2)),
+                segment((3, 2), (3, 2)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use crate::analysis::MazeMetrics;
+use crate::cell::Cell;
+use crate::ghost::{Trace, TracePoint};
+use crate::map::Map;
+use crate::player::Player;
+use crate::replay::{Input, Recording};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Length of one simulation step in seconds. Play always advances in whole
+// steps, whatever the frame rate, so recorded runs replay exactly.
+pub const TICK: f32 = 1.0 / 60.0;
+
+// Most frame time simulated at once, so a stalled frame doesn't run a burst
+// of steps
+pub(crate) const MAX_FRAME_TIME: f32 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    // Change in (x, y) for one step in this direction
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+}
+
+// One play-through of a map: the player, where they have been, and the
+// rules for moving and winning. Frontends feed it the direction held and
+// frame time.
+pub struct Session {
+    pub map: Map,
+    pub source: Option<String>, // File the map was loaded from or saved to
+    pub player: Player,
+    pub visited: HashSet<(usize, usize)>, // Cells the player has stood on
+    pub metrics: MazeMetrics,             // Analysis of the map, taken at the start
+    pub elapsed: f32,                     // Seconds since the first move, stopped on winning
+    pub moves: usize,                     // Steps taken
+    pub backtracks: usize,                // Steps onto cells already visited
+    pub assisted: bool,                   // Solution seen, even before a restart; never ranked
+    pub sight: Option<usize>,             // How far the player can see, None to see everything
+    pub ticks: u64,                       // Steps simulated so far
+    started: Option<u64>,                 // Tick of the first move
+    input: Option<Direction>,             // Direction held
+    inputs: Vec<Input>,                   // Every change of `input`, for recording
+    trace: Vec<TracePoint>,               // Every move started and finished
+    accumulator: f32,                     // Frame time not yet simulated
+}
+
+impl Session {
+    // Start a session with the player on the map's start
+    pub fn new(map: Map) -> Self {
+        let player = Player::new(map.start.0, map.start.1);
+        let mut visited = HashSet::new();
+        visited.insert(player.position);
+        Self {
+            metrics: map.metrics(),
+            map,
+            source: None,
+            player,
+            visited,
+            elapsed: 0.0,
+            moves: 0,
+            backtracks: 0,
+            assisted: false,
+            sight: None,
+            ticks: 0,
+            started: None,
+            input: None,
+            inputs: vec![],
+            trace: vec![],
+            accumulator: 0.0,
+        }
+    }
+
+    // Put the player back on the start and forget the run so far. A player
+    // who has seen the solution stays assisted, since they still know it.
+    pub fn restart(&mut self) {
+        self.player = Player::new(self.map.start.0, self.map.start.1);
+        self.visited.clear();
+        self.visited.insert(self.player.position);
+        self.elapsed = 0.0;
+        self.moves = 0;
+        self.backtracks = 0;
+        self.ticks = 0;
+        self.started = None;
+        self.input = None;
+        self.inputs.clear();
+        self.trace.clear();
+        self.accumulator = 0.0;
+    }
+
+    // Whether the player could step one cell in a direction from where they
+    // stand
+    pub fn can_move(&self, direction: Direction) -> bool {
+        let (dx, dy) = direction.offset();
+        let x = self.player.position.0.wrapping_add(dx as usize);
+        let y = self.player.position.1.wrapping_add(dy as usize);
+        matches!(self.map.get(x, y), Some(Cell::Path) | Some(Cell::Solution))
+    }
+
+    // Start moving one cell in a direction. Ignored once the maze is won,
+    // while a move is already in progress or when a wall is in the way;
+    // returns whether a move began. Moves made this way are not recorded;
+    // frontends should hold a direction with `set_input` instead.
+    pub fn try_move(&mut self, direction: Direction) -> bool {
+        if self.is_won() || self.player.is_moving || !self.can_move(direction) {
+            return false;
+        }
+        let (dx, dy) = direction.offset();
+        let target = (
+            self.player.position.0.wrapping_add(dx as usize),
+            self.player.position.1.wrapping_add(dy as usize),
+        );
+        self.moves += 1;
+        if self.visited.contains(&target) {
+            self.backtracks += 1;
+        }
+        self.player.direction = (dx, dy);
+        self.player.is_moving = true;
+        true
+    }
+
+    // Hold a direction, or none, until it is next changed. The player moves
+    // that way whenever a step allows.
+    pub fn set_input(&mut self, direction: Option<Direction>) {
+        if direction != self.input {
+            self.input = direction;
+            self.inputs.push(Input {
+                tick: self.ticks,
+                direction,
+            });
+        }
+    }
+
+    // Simulate as many whole ticks as fit in the frame time so far
+    pub fn update(&mut self, delta_time: f32) {
+        self.accumulator = (self.accumulator + delta_time).min(MAX_FRAME_TIME);
+        while self.accumulator >= TICK {
+            self.accumulator -= TICK;
+            self.tick();
+        }
+    }
+
+    // Advance the game by one tick: start a move in the held direction, then
+    // move the player along. The timer starts with the first move and stops
+    // on winning.
+    pub fn tick(&mut self) {
+        if let Some(direction) = self.input {
+            if self.try_move(direction) {
+                let started = *self.started.get_or_insert(self.ticks);
+                self.trace.push(TracePoint {
+                    tick: self.ticks - started,
+                    position: self.player.position,
+                });
+            }
+        }
+        if self.moves > 0 && !self.is_won() {
+            self.elapsed += TICK;
+        }
+        let was_moving = self.player.is_moving;
+        self.player.update_position(TICK);
+        self.ticks += 1;
+        if !self.player.is_moving {
+            self.visited.insert(self.player.position);
+            // Moves made directly with `try_move` have no start to count from
+            if was_moving {
+                if let Some(tick) = self.run_ticks() {
+                    self.trace.push(TracePoint {
+                        tick,
+                        position: self.player.position,
+                    });
+                }
+            }
+        }
+    }
+
+    // Ticks since the first move, None before it
+    pub fn run_ticks(&self) -> Option<u64> {
+        self.started.map(|started| self.ticks - started)
+    }
+
+    // Where the player was through the run so far
+    pub fn trace(&self) -> Trace {
+        Trace {
+            map_hash: self.map.content_hash(),
+            points: self.trace.clone(),
+        }
+    }
+
+    // The inputs of the run so far
+    pub fn recording(&self) -> Recording {
+        Recording {
+            map_hash: self.map.content_hash(),
+            inputs: self.inputs.clone(),
+            ticks: self.ticks,
+        }
+    }
+
+    // Cells the player can see from where they stand, None when the whole
+    // maze is visible
+    pub fn visible_cells(&self) -> Option<HashSet<(usize, usize)>> {
+        let radius = self.sight?;
+        Some(self.map.visible_from(self.player.position, radius))
+    }
+
+    // Whether the player is standing on a goal
+    pub fn is_won(&self) -> bool {
+        self.map.is_goal(self.player.position)
+    }
+
+    // Shortest route over steps taken, as a percentage. None before the
+    // first move or if the maze cannot be solved.
+    pub fn efficiency(&self) -> Option<f32> {
+        let optimal = self.metrics.solution_length?;
+        if self.moves == 0 {
+            return None;
+        }
+        Some(optimal as f32 * 100.0 / self.moves as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::maze;
+    use crate::generators::Algorithm;
+
+    #[test]
+    fn restart_keeps_the_run_assisted() {
+        let mut session = Session::new(maze(11, 11, Algorithm::RecursiveBacktracker, 2));
+        session.assisted = true;
+        session.restart();
+        assert!(session.assisted);
+        assert_eq!(session.moves, 0);
+    }
+}
@@ -0,0 +1,77 @@
+use super::{open_neighbors, reconstruct_path, SearchEvent, SolveResult, Solver};
+use crate::map::Map;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// A* search guided by the Manhattan distance to the nearest goal. Every move
+// costs at least 1, so the heuristic never overestimates and the path is
+// optimal.
+pub struct AStar;
+
+impl Solver for AStar {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        best_first(map, observe, |(x, y)| {
+            map.goals
+                .iter()
+                .map(|&(gx, gy)| (x.abs_diff(gx) + y.abs_diff(gy)) as u32)
+                .min()
+                .unwrap_or(0)
+        })
+    }
+}
+
+// Cheapest-first search over move costs, ordered by cost so far plus the
+// heuristic. Shared with Dijkstra, which uses a zero heuristic.
+pub(super) fn best_first(
+    map: &Map,
+    observe: &mut dyn FnMut(SearchEvent),
+    heuristic: impl Fn((usize, usize)) -> u32,
+) -> SolveResult {
+    let mut open = BinaryHeap::new();
+    let mut cost = vec![u32::MAX; map.width * map.height];
+    let mut closed = vec![false; map.width * map.height];
+    let mut came_from = vec![None; map.width * map.height];
+    let mut nodes_expanded = 0;
+
+    let start = map.start;
+    cost[start.1 * map.width + start.0] = 0;
+    open.push(Reverse((heuristic(start), 0, start)));
+    observe(SearchEvent::Frontier(start));
+
+    while let Some(Reverse((_, current_cost, current))) = open.pop() {
+        let index = current.1 * map.width + current.0;
+        if closed[index] {
+            continue;
+        }
+        closed[index] = true;
+        nodes_expanded += 1;
+        observe(SearchEvent::Visit(current));
+
+        if map.is_goal(current) {
+            return SolveResult {
+                path: Some(reconstruct_path(map, &came_from, current)),
+                nodes_expanded,
+            };
+        }
+
+        for (nx, ny) in open_neighbors(map, current) {
+            let next_index = ny * map.width + nx;
+            let next_cost = current_cost + map.move_cost(nx, ny).unwrap_or(u32::MAX);
+            if next_cost < cost[next_index] {
+                cost[next_index] = next_cost;
+                came_from[next_index] = Some(current);
+                open.push(Reverse((
+                    next_cost + heuristic((nx, ny)),
+                    next_cost,
+                    (nx, ny),
+                )));
+                observe(SearchEvent::Frontier((nx, ny)));
+            }
+        }
+    }
+
+    SolveResult {
+        path: None,
+        nodes_expanded,
+    }
+}
@@ -0,0 +1,46 @@
+use super::{open_neighbors, reconstruct_path, SearchEvent, SolveResult, Solver};
+use crate::map::Map;
+use std::collections::VecDeque;
+
+// Breadth-first search. Explores in order of distance, so the path is the
+// shortest route even in braided mazes with loops.
+pub struct Bfs;
+
+impl Solver for Bfs {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        let mut queue = VecDeque::new();
+        let mut visited = vec![false; map.width * map.height];
+        let mut came_from = vec![None; map.width * map.height];
+        let mut nodes_expanded = 0;
+
+        let start = map.start;
+        queue.push_back(start);
+        observe(SearchEvent::Frontier(start));
+        visited[start.1 * map.width + start.0] = true;
+
+        while let Some(current) = queue.pop_front() {
+            nodes_expanded += 1;
+            observe(SearchEvent::Visit(current));
+            if map.is_goal(current) {
+                return SolveResult {
+                    path: Some(reconstruct_path(map, &came_from, current)),
+                    nodes_expanded,
+                };
+            }
+
+            for (nx, ny) in open_neighbors(map, current) {
+                if !visited[ny * map.width + nx] {
+                    queue.push_back((nx, ny));
+                    observe(SearchEvent::Frontier((nx, ny)));
+                    visited[ny * map.width + nx] = true;
+                    came_from[ny * map.width + nx] = Some(current);
+                }
+            }
+        }
+
+        SolveResult {
+            path: None,
+            nodes_expanded,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::{open_neighbors, reconstruct_path, SearchEvent, SolveResult, Solver};
+use crate::map::Map;
+use std::collections::VecDeque;
+
+// Dead-end filling: repeatedly fills in dead ends until only routes between
+// the start and a goal remain. In a perfect maze what is left is exactly the
+// solution; loops in braided mazes survive and are resolved with a BFS.
+pub struct DeadEndFilling;
+
+impl Solver for DeadEndFilling {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        let mut filled = vec![false; map.width * map.height];
+        let mut nodes_expanded = 0;
+
+        let open_count = |filled: &[bool], cell: (usize, usize)| {
+            open_neighbors(map, cell)
+                .into_iter()
+                .filter(|&(x, y)| !filled[y * map.width + x])
+                .count()
+        };
+        let fillable = |cell: (usize, usize)| cell != map.start && !map.is_goal(cell);
+
+        // Seed with every dead end, then follow each corridor back
+        let mut queue: VecDeque<(usize, usize)> = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| map.move_cost(x, y).is_some())
+            .filter(|&cell| fillable(cell) && open_count(&filled, cell) <= 1)
+            .collect();
+        for &cell in &queue {
+            observe(SearchEvent::Frontier(cell));
+        }
+
+        while let Some(cell) = queue.pop_front() {
+            let index = cell.1 * map.width + cell.0;
+            if filled[index] || open_count(&filled, cell) > 1 {
+                continue;
+            }
+            filled[index] = true;
+            nodes_expanded += 1;
+            observe(SearchEvent::Visit(cell));
+
+            for neighbor in open_neighbors(map, cell) {
+                if !filled[neighbor.1 * map.width + neighbor.0]
+                    && fillable(neighbor)
+                    && open_count(&filled, neighbor) <= 1
+                {
+                    queue.push_back(neighbor);
+                    observe(SearchEvent::Frontier(neighbor));
+                }
+            }
+        }
+
+        // Trace the route through what is left
+        let mut came_from = vec![None; map.width * map.height];
+        let mut reached = vec![false; map.width * map.height];
+        let mut queue = VecDeque::from([map.start]);
+        reached[map.start.1 * map.width + map.start.0] = true;
+        while let Some(cell) = queue.pop_front() {
+            if map.is_goal(cell) {
+                return SolveResult {
+                    path: Some(reconstruct_path(map, &came_from, cell)),
+                    nodes_expanded,
+                };
+            }
+            for (nx, ny) in open_neighbors(map, cell) {
+                let next_index = ny * map.width + nx;
+                if !reached[next_index] && !filled[next_index] {
+                    reached[next_index] = true;
+                    came_from[next_index] = Some(cell);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        SolveResult {
+            path: None,
+            nodes_expanded,
+        }
+    }
+}
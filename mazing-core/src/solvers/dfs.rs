@@ -0,0 +1,46 @@
+use super::{open_neighbors, reconstruct_path, SearchEvent, SolveResult, Solver};
+use crate::map::Map;
+
+// Depth-first search. Finds a route quickly in perfect mazes, but not
+// necessarily the shortest one when the maze has loops.
+pub struct Dfs;
+
+impl Solver for Dfs {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        let mut stack = vec![map.start];
+        let mut visited = vec![false; map.width * map.height];
+        let mut came_from = vec![None; map.width * map.height];
+        let mut nodes_expanded = 0;
+        observe(SearchEvent::Frontier(map.start));
+
+        while let Some(current) = stack.pop() {
+            let index = current.1 * map.width + current.0;
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            nodes_expanded += 1;
+            observe(SearchEvent::Visit(current));
+
+            if map.is_goal(current) {
+                return SolveResult {
+                    path: Some(reconstruct_path(map, &came_from, current)),
+                    nodes_expanded,
+                };
+            }
+
+            for (nx, ny) in open_neighbors(map, current) {
+                if !visited[ny * map.width + nx] {
+                    came_from[ny * map.width + nx] = Some(current);
+                    stack.push((nx, ny));
+                    observe(SearchEvent::Frontier((nx, ny)));
+                }
+            }
+        }
+
+        SolveResult {
+            path: None,
+            nodes_expanded,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use super::astar::best_first;
+use super::{SearchEvent, SolveResult, Solver};
+use crate::map::Map;
+
+// Dijkstra's algorithm: cheapest route by the map's move costs, for terrain
+// where cells are not all equally expensive to cross
+pub struct Dijkstra;
+
+impl Solver for Dijkstra {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        best_first(map, observe, |_| 0)
+    }
+}
@@ -0,0 +1,274 @@
+use crate::map::Map;
+use std::str::FromStr;
+
+mod astar;
+mod bfs;
+mod dead_end_filling;
+mod dfs;
+mod dijkstra;
+mod tremaux;
+mod wall_follower;
+
+pub use astar::AStar;
+pub use bfs::Bfs;
+pub use dead_end_filling::DeadEndFilling;
+pub use dfs::Dfs;
+pub use dijkstra::Dijkstra;
+pub use tremaux::Tremaux;
+pub use wall_follower::{Hand, WallFollower};
+
+// Outcome of running a solver on a map
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolveResult {
+    pub path: Option<Vec<(usize, usize)>>, // Start to goal, both included
+    pub nodes_expanded: usize,             // Cells the solver had to process
+}
+
+impl SolveResult {
+    // Number of moves along the path
+    pub fn path_length(&self) -> Option<usize> {
+        self.path.as_ref().map(|path| path.len() - 1)
+    }
+}
+
+// A step of a running search, for visualising how a solver explores
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchEvent {
+    Frontier((usize, usize)), // Queued for exploration
+    Visit((usize, usize)),    // Taken off the frontier and expanded
+}
+
+// A maze solving algorithm. Solvers never modify the map.
+pub trait Solver {
+    // Solve while reporting every step of the search to `observe`
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult;
+
+    fn solve(&self, map: &Map) -> SolveResult {
+        self.solve_observed(map, &mut |_| {})
+    }
+
+    // Solve and collect the search steps in order
+    fn solve_traced(&self, map: &Map) -> (SolveResult, Vec<SearchEvent>) {
+        let mut events = Vec::new();
+        let result = self.solve_observed(map, &mut |event| events.push(event));
+        (result, events)
+    }
+}
+
+// Selectable solving algorithms
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolverKind {
+    #[default]
+    Bfs,
+    Dfs,
+    AStar,
+    Dijkstra,
+    WallFollower(Hand),
+    Tremaux,
+    DeadEndFilling,
+}
+
+impl SolverKind {
+    pub const ALL: [SolverKind; 8] = [
+        SolverKind::Bfs,
+        SolverKind::Dfs,
+        SolverKind::AStar,
+        SolverKind::Dijkstra,
+        SolverKind::WallFollower(Hand::Left),
+        SolverKind::WallFollower(Hand::Right),
+        SolverKind::Tremaux,
+        SolverKind::DeadEndFilling,
+    ];
+
+    // Human readable name for menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolverKind::Bfs => "BFS",
+            SolverKind::Dfs => "DFS",
+            SolverKind::AStar => "A*",
+            SolverKind::Dijkstra => "Dijkstra",
+            SolverKind::WallFollower(Hand::Left) => "Left Hand",
+            SolverKind::WallFollower(Hand::Right) => "Right Hand",
+            SolverKind::Tremaux => "Tremaux",
+            SolverKind::DeadEndFilling => "Dead-End Filling",
+        }
+    }
+
+    // Short name used on the command line
+    pub fn slug(&self) -> &'static str {
+        match self {
+            SolverKind::Bfs => "bfs",
+            SolverKind::Dfs => "dfs",
+            SolverKind::AStar => "astar",
+            SolverKind::Dijkstra => "dijkstra",
+            SolverKind::WallFollower(Hand::Left) => "left-hand",
+            SolverKind::WallFollower(Hand::Right) => "right-hand",
+            SolverKind::Tremaux => "tremaux",
+            SolverKind::DeadEndFilling => "dead-end-filling",
+        }
+    }
+
+    // The next solver in menu order, wrapping around
+    pub fn next(&self) -> SolverKind {
+        let index = SolverKind::ALL
+            .iter()
+            .position(|kind| kind == self)
+            .unwrap();
+        SolverKind::ALL[(index + 1) % SolverKind::ALL.len()]
+    }
+
+    // Build the solver implementing this algorithm
+    pub fn solver(&self) -> Box<dyn Solver> {
+        match self {
+            SolverKind::Bfs => Box::new(Bfs),
+            SolverKind::Dfs => Box::new(Dfs),
+            SolverKind::AStar => Box::new(AStar),
+            SolverKind::Dijkstra => Box::new(Dijkstra),
+            SolverKind::WallFollower(hand) => Box::new(WallFollower { hand: *hand }),
+            SolverKind::Tremaux => Box::new(Tremaux),
+            SolverKind::DeadEndFilling => Box::new(DeadEndFilling),
+        }
+    }
+}
+
+impl FromStr for SolverKind {
+    type Err = String;
+
+    // Parse a command line name, as given by `slug`
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        SolverKind::ALL
+            .iter()
+            .find(|kind| kind.slug() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown solver '{}'", name))
+    }
+}
+
+// Up, right, down, left; clockwise so wall followers can turn by index
+const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Step one cell in a direction, if that stays on the map
+fn step(map: &Map, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+    let nx = x.checked_add_signed(dx)?;
+    let ny = y.checked_add_signed(dy)?;
+    (nx < map.width && ny < map.height).then_some((nx, ny))
+}
+
+// Walkable cells next to a position
+fn open_neighbors(map: &Map, position: (usize, usize)) -> Vec<(usize, usize)> {
+    DIRECTIONS
+        .iter()
+        .filter_map(|&direction| step(map, position, direction))
+        .filter(|&(x, y)| map.move_cost(x, y).is_some())
+        .collect()
+}
+
+// Walk `came_from` links back from the goal and return the path start first
+fn reconstruct_path(
+    map: &Map,
+    came_from: &[Option<(usize, usize)>],
+    end: (usize, usize),
+) -> Vec<(usize, usize)> {
+    let mut current = end;
+    let mut path = vec![current];
+    while let Some(prev) = came_from[current.1 * map.width + current.0] {
+        path.push(prev);
+        current = prev;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::fixtures::{braided, maze};
+    use crate::generators::Algorithm;
+    use std::collections::VecDeque;
+
+    // Shortest distance to a goal by a plain breadth-first flood, kept
+    // separate from the solvers under test
+    fn shortest(map: &Map) -> Option<usize> {
+        let mut distance = vec![None; map.width * map.height];
+        let mut queue = VecDeque::from([map.start]);
+        distance[map.start.1 * map.width + map.start.0] = Some(0);
+        while let Some(position) = queue.pop_front() {
+            let steps = distance[position.1 * map.width + position.0].unwrap();
+            if map.is_goal(position) {
+                return Some(steps);
+            }
+            for (x, y) in open_neighbors(map, position) {
+                if distance[y * map.width + x].is_none() {
+                    distance[y * map.width + x] = Some(steps + 1);
+                    queue.push_back((x, y));
+                }
+            }
+        }
+        None
+    }
+
+    // A route from the start to a goal through open cells, one step at a time
+    fn assert_valid_path(map: &Map, path: &[(usize, usize)], kind: SolverKind) {
+        assert_eq!(path.first(), Some(&map.start), "{} start", kind.name());
+        assert!(map.is_goal(*path.last().unwrap()), "{} goal", kind.name());
+        for &(x, y) in path {
+            assert!(map.move_cost(x, y).is_some(), "{} wall", kind.name());
+        }
+        for pair in path.windows(2) {
+            let gap = pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1);
+            assert_eq!(gap, 1, "{} jumps", kind.name());
+        }
+    }
+
+    #[test]
+    fn shortest_path_solvers_are_optimal_on_braided_mazes() {
+        for algorithm in Algorithm::ALL {
+            for seed in 0..5 {
+                let map = braided(21, 15, algorithm, seed, 0.6);
+                let expected = shortest(&map);
+                for kind in [SolverKind::Bfs, SolverKind::AStar, SolverKind::Dijkstra] {
+                    let length = kind.solver().solve(&map).path_length();
+                    assert_eq!(length, expected, "{} on {:?}", kind.name(), algorithm);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_solver_walks_from_start_to_goal() {
+        for algorithm in Algorithm::ALL {
+            for seed in 0..5 {
+                // Without loops every solver, wall followers included, must
+                // find a way out
+                let perfect = maze(21, 15, algorithm, seed);
+                for kind in SolverKind::ALL {
+                    let path = kind.solver().solve(&perfect).path;
+                    let path = path.unwrap_or_else(|| panic!("{} found no path", kind.name()));
+                    assert_valid_path(&perfect, &path, kind);
+                }
+
+                let looped = braided(21, 15, algorithm, seed, 1.0);
+                for kind in SolverKind::ALL {
+                    if let Some(path) = kind.solver().solve(&looped).path {
+                        assert_valid_path(&looped, &path, kind);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsolvable_map_has_no_path() {
+        // Wall the exit in from the inside
+        let mut map = maze(21, 15, Algorithm::RecursiveBacktracker, 1);
+        let (x, y) = map.goals[0];
+        for (nx, ny) in open_neighbors(&map, (x, y)) {
+            map.set(nx, ny, Cell::Wall);
+        }
+        assert_eq!(shortest(&map), None);
+        for kind in SolverKind::ALL {
+            assert_eq!(kind.solver().solve(&map).path, None, "{}", kind.name());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use super::{open_neighbors, reconstruct_path, SearchEvent, SolveResult, Solver};
+use crate::map::Map;
+use std::collections::{HashMap, VecDeque};
+
+// Tremaux's algorithm: walks the maze marking each passage as it goes. A
+// passage is never entered a third time, so it terminates on any maze, and
+// the passages marked exactly once form the route.
+pub struct Tremaux;
+
+impl Solver for Tremaux {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        let mut marks: HashMap<Passage, u8> = HashMap::new();
+        let mut visited = vec![false; map.width * map.height];
+        let mut nodes_expanded = 0;
+
+        let mut current = map.start;
+        let mut previous: Option<(usize, usize)> = None;
+
+        loop {
+            let index = current.1 * map.width + current.0;
+            let seen_before = visited[index];
+            visited[index] = true;
+
+            if map.is_goal(current) {
+                break;
+            }
+
+            let marked = |marks: &HashMap<_, u8>, other| {
+                marks.get(&passage(current, other)).copied().unwrap_or(0)
+            };
+            let came_back = previous.map_or(0, |prev| marked(&marks, prev));
+            let neighbors = open_neighbors(map, current);
+
+            let next = match previous {
+                // Reached a known cell through a fresh passage: turn around
+                Some(prev) if seen_before && came_back == 1 => Some(prev),
+                _ => neighbors
+                    .iter()
+                    .copied()
+                    .find(|&other| Some(other) != previous && marked(&marks, other) == 0)
+                    .or_else(|| previous.filter(|_| came_back < 2))
+                    .or_else(|| {
+                        neighbors
+                            .iter()
+                            .copied()
+                            .filter(|&other| marked(&marks, other) < 2)
+                            .min_by_key(|&other| marked(&marks, other))
+                    }),
+            };
+
+            // Every passage is used up: no goal can be reached
+            let Some(next) = next else {
+                return SolveResult {
+                    path: None,
+                    nodes_expanded,
+                };
+            };
+
+            *marks.entry(passage(current, next)).or_insert(0) += 1;
+            previous = Some(current);
+            current = next;
+            nodes_expanded += 1;
+            observe(SearchEvent::Visit(current));
+        }
+
+        // The route follows passages walked exactly once
+        let mut came_from = vec![None; map.width * map.height];
+        let mut reached = vec![false; map.width * map.height];
+        let mut queue = VecDeque::from([map.start]);
+        reached[map.start.1 * map.width + map.start.0] = true;
+        while let Some(cell) = queue.pop_front() {
+            for (nx, ny) in open_neighbors(map, cell) {
+                let next_index = ny * map.width + nx;
+                if !reached[next_index] && marks.get(&passage(cell, (nx, ny))) == Some(&1) {
+                    reached[next_index] = true;
+                    came_from[next_index] = Some(cell);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        SolveResult {
+            path: Some(reconstruct_path(map, &came_from, current)),
+            nodes_expanded,
+        }
+    }
+}
+
+// The connection between two adjacent cells, independent of direction
+type Passage = ((usize, usize), (usize, usize));
+
+fn passage(a: (usize, usize), b: (usize, usize)) -> Passage {
+    (a.min(b), a.max(b))
+}
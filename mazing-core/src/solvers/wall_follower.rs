@@ -0,0 +1,111 @@
+use super::{step, SearchEvent, SolveResult, Solver, DIRECTIONS};
+use crate::map::Map;
+
+// Which wall the follower keeps a hand on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+// Wall follower: walks with one hand on the wall. Needs no memory, but can
+// circle forever around a loop that does not touch the goal, and the route
+// includes every detour it walks.
+pub struct WallFollower {
+    pub hand: Hand,
+}
+
+impl Solver for WallFollower {
+    fn solve_observed(&self, map: &Map, observe: &mut dyn FnMut(SearchEvent)) -> SolveResult {
+        let is_open = |position: Option<(usize, usize)>| {
+            position.is_some_and(|(x, y)| map.move_cost(x, y).is_some())
+        };
+
+        // Preferred turns relative to the current heading, as clockwise
+        // quarter turns: towards the hand, straight, away, then back
+        let turns = match self.hand {
+            Hand::Left => [3, 0, 1, 2],
+            Hand::Right => [1, 0, 3, 2],
+        };
+
+        // Start facing any open direction
+        let mut current = map.start;
+        let Some(mut heading) =
+            (0..4).find(|&direction| is_open(step(map, current, DIRECTIONS[direction])))
+        else {
+            return SolveResult::default();
+        };
+
+        // The walk is decided by the cell and heading alone, so making its
+        // first move a second time means it is going round in circles
+        let mut first_move = None;
+
+        let mut path = vec![current];
+        let mut nodes_expanded = 0;
+        while !map.is_goal(current) {
+            let Some(next_heading) = turns
+                .iter()
+                .map(|turn| (heading + turn) % 4)
+                .find(|&direction| is_open(step(map, current, DIRECTIONS[direction])))
+            else {
+                return SolveResult {
+                    path: None,
+                    nodes_expanded,
+                };
+            };
+
+            // Every (cell, heading) pair has been used once it has walked
+            // this far, so it cannot be making progress either
+            if first_move == Some((current, next_heading))
+                || nodes_expanded > 4 * map.width * map.height
+            {
+                return SolveResult {
+                    path: None,
+                    nodes_expanded,
+                };
+            }
+            first_move.get_or_insert((current, next_heading));
+
+            heading = next_heading;
+            current = step(map, current, DIRECTIONS[heading]).unwrap();
+            path.push(current);
+            nodes_expanded += 1;
+            observe(SearchEvent::Visit(current));
+        }
+
+        SolveResult {
+            path: Some(path),
+            nodes_expanded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::Bfs;
+
+    // The start touches a free-standing wall that the walker circles
+    // without ever reaching the outer wall, so it must give up
+    const ISLAND: &str = "\
+#########
+#       #
+#       #
+#    S  #
+#   #   #
+#       #
+#       #
+#       #
+#######E#
+";
+
+    #[test]
+    fn gives_up_circling_an_island() {
+        let map = Map::from_ascii(ISLAND).unwrap();
+        assert_eq!(Bfs.solve(&map).path_length(), Some(7));
+        for hand in [Hand::Left, Hand::Right] {
+            let result = WallFollower { hand }.solve(&map);
+            assert!(result.path.is_none());
+        }
+    }
+}
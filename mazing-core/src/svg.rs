@@ -0,0 +1,138 @@
+use crate::map::Map;
+use std::fmt::Write;
+
+// How `Map::to_svg` draws a maze. Colours are any SVG colour value; they
+// are escaped when written, so they can't break out of their attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    pub cell_size: f32,
+    pub stroke_width: f32, // Wall thickness; `cell_size` gives solid blocks
+    pub margin: f32,       // Blank space around the maze
+    pub wall_color: String,
+    pub background: String,
+    pub solution_color: String,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 10.0,
+            stroke_width: 2.0,
+            margin: 10.0,
+            wall_color: "black".to_string(),
+            background: "white".to_string(),
+            solution_color: "red".to_string(),
+        }
+    }
+}
+
+impl Map {
+    // Render the map as an SVG document. Walls are stroked as merged line
+    // segments (see `wall_segments`) in a single path, and the solution (if
+    // given) is a polyline through the cell centres.
+    pub fn to_svg(&self, options: &SvgOptions, solution: Option<&[(usize, usize)]>) -> String {
+        let width = self.width as f32 * options.cell_size + 2.0 * options.margin;
+        let height = self.height as f32 * options.cell_size + 2.0 * options.margin;
+        let centre = |(x, y): (usize, usize)| {
+            (
+                options.margin + (x as f32 + 0.5) * options.cell_size,
+                options.margin + (y as f32 + 0.5) * options.cell_size,
+            )
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            width, height, width, height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            escape(&options.background)
+        );
+
+        let mut walls = String::new();
+        for segment in self.wall_segments() {
+            let (x1, y1) = centre(segment.from);
+            let (x2, y2) = centre(segment.to);
+            let _ = write!(walls, "M{} {}L{} {}", x1, y1, x2, y2);
+        }
+        let _ = writeln!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="square"/>"#,
+            walls,
+            escape(&options.wall_color),
+            options.stroke_width
+        );
+
+        if let Some(path) = solution {
+            let points: Vec<String> = path
+                .iter()
+                .map(|&position| {
+                    let (x, y) = centre(position);
+                    format!("{},{}", x, y)
+                })
+                .collect();
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+                points.join(" "),
+                escape(&options.solution_color),
+                options.cell_size / 3.0
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// Text made safe to put inside an XML attribute
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::from_rows;
+
+    #[test]
+    fn draws_walls_and_solution() {
+        let map = from_rows(&["###", "# #", "###"]);
+        let svg = map.to_svg(&SvgOptions::default(), Some(&[(1, 1)]));
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches('M').count(), map.wall_segments().len());
+        assert!(svg.contains(r#"stroke="black""#));
+        assert!(svg.contains(r#"<polyline points="25,25""#));
+    }
+
+    #[test]
+    fn colours_are_escaped() {
+        let options = SvgOptions {
+            wall_color: r#""/><script>x</script>"#.to_string(),
+            background: "a&b".to_string(),
+            solution_color: "'red'".to_string(),
+            ..SvgOptions::default()
+        };
+        let map = from_rows(&["###", "# #", "###"]);
+        let svg = map.to_svg(&options, Some(&[(1, 1)]));
+        assert!(!svg.contains("<script>"));
+        assert!(svg.contains(r#"stroke="&quot;/&gt;&lt;script&gt;x&lt;/script&gt;""#));
+        assert!(svg.contains(r#"fill="a&amp;b""#));
+        assert!(svg.contains(r#"stroke="&apos;red&apos;""#));
+    }
+}
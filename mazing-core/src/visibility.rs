@@ -0,0 +1,131 @@
+use crate::map::Map;
+use std::collections::HashSet;
+
+impl Map {
+    // Cells that can be seen from `from` within `radius` cells: every cell,
+    // open or wall, whose centre is in range and in line of sight
+    pub fn visible_from(&self, from: (usize, usize), radius: usize) -> HashSet<(usize, usize)> {
+        let mut visible = HashSet::new();
+        let min_x = from.0.saturating_sub(radius);
+        let min_y = from.1.saturating_sub(radius);
+        let max_x = (from.0 + radius).min(self.width.saturating_sub(1));
+        let max_y = (from.1 + radius).min(self.height.saturating_sub(1));
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (dx, dy) = (x.abs_diff(from.0), y.abs_diff(from.1));
+                if dx * dx + dy * dy <= radius * radius && self.line_of_sight(from, (x, y)) {
+                    visible.insert((x, y));
+                }
+            }
+        }
+        visible
+    }
+
+    // Whether two cells can see each other. Bresenham's line from one end
+    // doesn't always pass through the same cells as the line drawn back
+    // from the other, so the line must be clear both ways and sight is
+    // always mutual.
+    pub fn line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        self.clear_line(from, to) && self.clear_line(to, from)
+    }
+
+    // Whether the straight line from the centre of `from` to the centre of
+    // `to` crosses only open cells on the way. The cells at either end may
+    // be walls. A diagonal step is blocked when walls close both sides of
+    // it, so sight doesn't leak between corners that only touch.
+    fn clear_line(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let is_open = |(x, y): (isize, isize)| self.move_cost(x as usize, y as usize).is_some();
+        let (x1, y1) = (to.0 as isize, to.1 as isize);
+        let (dx, dy) = ((x1 - from.0 as isize).abs(), -(y1 - from.1 as isize).abs());
+        let (step_x, step_y) = (
+            (x1 - from.0 as isize).signum(),
+            (y1 - from.1 as isize).signum(),
+        );
+        let (mut x, mut y) = (from.0 as isize, from.1 as isize);
+        let mut error = dx + dy;
+
+        // Bresenham's line, checking each cell before stepping past it
+        while (x, y) != (x1, y1) {
+            if (x, y) != (from.0 as isize, from.1 as isize) && !is_open((x, y)) {
+                return false;
+            }
+            let double = 2 * error;
+            let move_x = double >= dy;
+            let move_y = double <= dx;
+            if move_x && move_y && !is_open((x + step_x, y)) && !is_open((x, y + step_y)) {
+                return false;
+            }
+            if move_x {
+                error += dy;
+                x += step_x;
+            }
+            if move_y {
+                error += dx;
+                y += step_y;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{from_rows, maze};
+    use crate::generators::Algorithm;
+
+    #[test]
+    fn walls_block_sight() {
+        let map = from_rows(&[
+            "#######", //
+            "#  #  #", //
+            "#######",
+        ]);
+        assert!(map.line_of_sight((1, 1), (2, 1)));
+        assert!(map.line_of_sight((1, 1), (3, 1)));
+        assert!(!map.line_of_sight((1, 1), (4, 1)));
+        assert!(!map.visible_from((1, 1), 10).contains(&(5, 1)));
+    }
+
+    #[test]
+    fn radius_limits_sight() {
+        let map = from_rows(&[
+            "#########", //
+            "#       #", //
+            "#       #", //
+            "#       #", //
+            "#########",
+        ]);
+        let visible = map.visible_from((1, 1), 3);
+        assert!(visible.contains(&(4, 1)));
+        assert!(!visible.contains(&(5, 1)));
+        assert!(visible.contains(&(3, 3)));
+        assert!(!visible.contains(&(4, 3)));
+    }
+
+    #[test]
+    fn no_sight_through_touching_corners() {
+        let map = from_rows(&[
+            "#####", //
+            "# ###", //
+            "## ##", //
+            "### #", //
+            "#####",
+        ]);
+        assert!(!map.line_of_sight((1, 1), (2, 2)));
+        assert!(!map.line_of_sight((2, 2), (1, 1)));
+        assert!(!map.visible_from((1, 1), 5).contains(&(3, 3)));
+    }
+
+    #[test]
+    fn sight_is_mutual() {
+        let mut maze = maze(15, 11, Algorithm::RecursiveBacktracker, 3);
+        maze.braid(0.5);
+        for a in 0..maze.width * maze.height {
+            for b in 0..maze.width * maze.height {
+                let from = (a % maze.width, a / maze.width);
+                let to = (b % maze.width, b / maze.width);
+                assert_eq!(maze.line_of_sight(from, to), maze.line_of_sight(to, from));
+            }
+        }
+    }
+}
@@ -1,12 +1,23 @@
-use crate::game::{Cell, Map, Player};
 use crate::ui::draw_button;
-use crate::utils::{get_maps_list, key_to_char, key_to_digit};
+use crate::utils::{key_to_char, key_to_digit};
+use mazing_core::difficulty::generate_for_difficulty;
+use mazing_core::metadata::format_date;
+use mazing_core::{
+    Algorithm, Cell, Difficulty, DifficultyRequest, Direction, Leaderboard, Map, Recording, Replay,
+    Session, Trace,
+};
 use raylib::prelude::*;
-use std::collections::HashSet;
 use std::fs::{self};
 use std::path::Path;
+use std::time::Duration;
 
-use super::state::{GameState, NewGameState};
+use super::state::{
+    Completion, GameState, GenerationAnimation, MapEntry, MazeCamera, NewGameState, PlayView,
+    SolutionView,
+};
+
+// How many cells the player can see when fog of war is on
+const FOG_SIGHT: usize = 5;
 
 pub fn run(map_path: Option<String>) {
     // Initialize the window and graphics
@@ -25,19 +36,17 @@ pub fn run(map_path: Option<String>) {
     }
 
     let mut state = GameState::MainMenu;
-    let mut map_option = None;
-    let mut player = Player::new(1, 0);
-
-    let mut visited_positions = HashSet::new();
-    let mut show_solution = false;
+    let mut session = None;
+    let mut solution = SolutionView::default();
 
     // If a map path is provided, attempt to load it
     if let Some(path) = map_path {
         match Map::load_from_file(&path) {
             Ok(loaded_map) => {
-                map_option = Some(loaded_map);
-                state = GameState::Playing;
-                visited_positions.insert(player.position);
+                let mut loaded = Session::new(loaded_map);
+                loaded.source = Some(path);
+                state = GameState::Playing(PlayView::new(&loaded));
+                session = Some(loaded);
             }
             Err(e) => {
                 println!("Failed to load map from {}: {}", path, e);
@@ -63,9 +72,19 @@ pub fn run(map_path: Option<String>) {
                 if let Some(new_state) = main_menu(
                     &mut rl,
                     &thread,
-                    &mut map_option,
-                    &mut visited_positions,
-                    &mut show_solution,
+                    &mut session,
+                    &mut solution,
+                    window_width,
+                    window_height,
+                ) {
+                    state = new_state;
+                }
+            }
+            GameState::NewGameSelectDifficulty(ref mut new_game_state) => {
+                if let Some(new_state) = new_game_select_difficulty(
+                    &mut rl,
+                    &thread,
+                    new_game_state,
                     window_width,
                     window_height,
                 ) {
@@ -94,43 +113,57 @@ pub fn run(map_path: Option<String>) {
                     state = new_state;
                 }
             }
+            GameState::NewGameSelectAlgorithm(ref mut new_game_state) => {
+                if let Some(new_state) = new_game_select_algorithm(
+                    &mut rl,
+                    &thread,
+                    new_game_state,
+                    window_width,
+                    window_height,
+                ) {
+                    state = new_state;
+                }
+            }
             GameState::NewGameEnterName(ref mut new_game_state) => {
                 if let Some(new_state) = new_game_enter_name(
                     &mut rl,
                     &thread,
                     new_game_state,
-                    &mut map_option,
-                    &mut player,
-                    &mut visited_positions,
+                    &mut session,
                     window_width,
                     window_height,
                 ) {
                     state = new_state;
                 }
             }
-            GameState::LoadGame => {
+            GameState::Generating(ref mut animation) => {
+                if let Some(new_state) =
+                    generating(&mut rl, &thread, animation, maze_width, window_height)
+                {
+                    state = new_state;
+                }
+            }
+            GameState::LoadGame(ref entries) => {
                 if let Some(new_state) = load_game(
                     &mut rl,
                     &thread,
-                    &mut map_option,
-                    &mut player,
-                    &mut visited_positions,
-                    &mut show_solution,
+                    entries,
+                    &mut session,
+                    &mut solution,
                     window_width,
                     window_height,
                 ) {
                     state = new_state;
                 }
             }
-            GameState::Playing => {
+            GameState::Playing(ref mut view) => {
                 if let Some(new_state) = playing(
                     &mut rl,
                     &thread,
                     delta_time,
-                    &mut map_option,
-                    &mut player,
-                    &mut visited_positions,
-                    &mut show_solution,
+                    &mut session,
+                    view,
+                    &mut solution,
                     maze_width,
                     ui_width,
                     window_width,
@@ -139,6 +172,18 @@ pub fn run(map_path: Option<String>) {
                     state = new_state;
                 }
             }
+            GameState::Completed(ref mut completion) => {
+                if let Some(new_state) = completed(
+                    &mut rl,
+                    &thread,
+                    completion,
+                    &mut session,
+                    &mut solution,
+                    window_width,
+                ) {
+                    state = new_state;
+                }
+            }
         }
     }
 }
@@ -147,9 +192,8 @@ pub fn run(map_path: Option<String>) {
 fn main_menu(
     rl: &mut RaylibHandle,
     thread: &RaylibThread,
-    map_option: &mut Option<Map>,
-    visited_positions: &mut HashSet<(usize, usize)>,
-    show_solution: &mut bool,
+    session: &mut Option<Session>,
+    solution: &mut SolutionView,
     window_width: f32,
     window_height: f32,
 ) -> Option<GameState> {
@@ -168,21 +212,22 @@ fn main_menu(
 
     if mouse_left_pressed {
         if new_game_hovered {
-            // Start new game setup by entering width
+            // Start new game setup by choosing a difficulty
             let new_game_state = NewGameState {
                 width_input: String::new(),
                 height_input: String::new(),
                 name_input: String::new(),
+                difficulty: None,
+                algorithm: Algorithm::RecursiveBacktracker,
+                braid: 0.0,
             };
-            *map_option = None;
-            visited_positions.clear();
-            *show_solution = false;
-            return Some(GameState::NewGameEnterWidth(new_game_state));
+            *session = None;
+            solution.clear();
+            return Some(GameState::NewGameSelectDifficulty(new_game_state));
         } else if load_game_hovered {
             // Load game
-            visited_positions.clear();
-            *show_solution = false;
-            return Some(GameState::LoadGame);
+            solution.clear();
+            return Some(GameState::LoadGame(MapEntry::scan()));
         }
     }
 
@@ -216,6 +261,75 @@ fn main_menu(
     None
 }
 
+// Function for choosing a difficulty, or a custom size
+fn new_game_select_difficulty(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    new_game_state: &mut NewGameState,
+    window_width: f32,
+    _window_height: f32,
+) -> Option<GameState> {
+    // Cache values before drawing
+    let mouse_pos = rl.get_mouse_position();
+    let mouse_left_pressed = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+    // Back button
+    let back_button_rect = Rectangle::new(20.0, 20.0, 100.0, 40.0);
+    let back_button_hovered = back_button_rect.check_collision_point_rec(mouse_pos);
+    let back_button_clicked = back_button_hovered && mouse_left_pressed;
+
+    // Draw UI for difficulty selection
+    let mut d = rl.begin_drawing(thread);
+    d.clear_background(Color::WHITE);
+
+    let prompt_text = "Select difficulty:";
+    let prompt_font_size = 30;
+    let prompt_width = d.measure_text(prompt_text, prompt_font_size) as f32;
+    d.draw_text(
+        prompt_text,
+        ((window_width - prompt_width) / 2.0) as i32,
+        200,
+        prompt_font_size,
+        Color::BLACK,
+    );
+
+    // One button per difficulty, then one for typing in a size
+    let start_y = 260.0;
+    let mut choices: Vec<Option<Difficulty>> = Difficulty::ALL.into_iter().map(Some).collect();
+    choices.push(None);
+    for (i, &difficulty) in choices.iter().enumerate() {
+        let button_rect = Rectangle::new(
+            (window_width - 300.0) / 2.0,
+            start_y + i as f32 * 60.0,
+            300.0,
+            50.0,
+        );
+        let button_hovered = button_rect.check_collision_point_rec(mouse_pos);
+        let label = difficulty.map_or("Custom Size", Difficulty::name);
+
+        draw_button(&mut d, &button_rect, label, button_hovered);
+
+        if button_hovered && mouse_left_pressed {
+            new_game_state.difficulty = difficulty;
+            return Some(match difficulty {
+                // The size follows from the difficulty
+                Some(_) => GameState::NewGameSelectAlgorithm(new_game_state.clone()),
+                None => GameState::NewGameEnterWidth(new_game_state.clone()),
+            });
+        }
+    }
+
+    // Draw Back button
+    draw_button(&mut d, &back_button_rect, "Back", back_button_hovered);
+
+    // Handle Back button click
+    if back_button_clicked {
+        return Some(GameState::MainMenu);
+    }
+
+    None
+}
+
 // Function for entering maze width
 fn new_game_enter_width(
     rl: &mut RaylibHandle,
@@ -310,8 +424,8 @@ fn new_game_enter_height(
         } else if key == KeyboardKey::KEY_BACKSPACE {
             new_game_state.height_input.pop();
         } else if key == KeyboardKey::KEY_ENTER {
-            // Proceed to map name input
-            return Some(GameState::NewGameEnterName(new_game_state.clone()));
+            // Proceed to algorithm selection
+            return Some(GameState::NewGameSelectAlgorithm(new_game_state.clone()));
         }
     }
 
@@ -374,20 +488,111 @@ fn new_game_enter_height(
     None
 }
 
+// Function for choosing the maze generation algorithm
+fn new_game_select_algorithm(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    new_game_state: &mut NewGameState,
+    window_width: f32,
+    _window_height: f32,
+) -> Option<GameState> {
+    // Cache values before drawing
+    let mouse_pos = rl.get_mouse_position();
+    let mouse_left_pressed = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+    // Back button
+    let back_button_rect = Rectangle::new(20.0, 20.0, 100.0, 40.0);
+    let back_button_hovered = back_button_rect.check_collision_point_rec(mouse_pos);
+    let back_button_clicked = back_button_hovered && mouse_left_pressed;
+
+    // Loops button, cycles how many dead ends get braided away
+    let loops_button_rect = Rectangle::new(window_width - 220.0, 20.0, 200.0, 40.0);
+    let loops_button_hovered = loops_button_rect.check_collision_point_rec(mouse_pos);
+    if loops_button_hovered && mouse_left_pressed {
+        new_game_state.braid = if new_game_state.braid >= 1.0 {
+            0.0
+        } else {
+            new_game_state.braid + 0.25
+        };
+    }
+
+    // Draw UI for algorithm selection
+    let mut d = rl.begin_drawing(thread);
+    d.clear_background(Color::WHITE);
+
+    let prompt_text = "Select maze algorithm:";
+    let prompt_font_size = 30;
+    let prompt_width = d.measure_text(prompt_text, prompt_font_size) as f32;
+    d.draw_text(
+        prompt_text,
+        ((window_width - prompt_width) / 2.0) as i32,
+        50,
+        prompt_font_size,
+        Color::BLACK,
+    );
+
+    // One button per algorithm
+    let start_y = 100.0;
+    for (i, algorithm) in Algorithm::ALL.iter().enumerate() {
+        let button_rect = Rectangle::new(
+            (window_width - 400.0) / 2.0,
+            start_y + i as f32 * 50.0,
+            400.0,
+            44.0,
+        );
+        let button_hovered = button_rect.check_collision_point_rec(mouse_pos);
+
+        draw_button(&mut d, &button_rect, algorithm.name(), button_hovered);
+
+        if button_hovered && mouse_left_pressed {
+            // Proceed to map name input
+            new_game_state.algorithm = *algorithm;
+            return Some(GameState::NewGameEnterName(new_game_state.clone()));
+        }
+    }
+
+    // Draw Loops button
+    let loops_text = format!("Loops: {}%", (new_game_state.braid * 100.0) as i32);
+    draw_button(
+        &mut d,
+        &loops_button_rect,
+        &loops_text,
+        loops_button_hovered,
+    );
+
+    // Draw Back button
+    draw_button(&mut d, &back_button_rect, "Back", back_button_hovered);
+
+    // Handle Back button click
+    if back_button_clicked {
+        return Some(GameState::MainMenu);
+    }
+
+    None
+}
+
 // Function for entering map name and generating the maze
 fn new_game_enter_name(
     rl: &mut RaylibHandle,
     thread: &RaylibThread,
     new_game_state: &mut NewGameState,
-    map_option: &mut Option<Map>,
-    player: &mut Player,
-    visited_positions: &mut HashSet<(usize, usize)>,
+    session: &mut Option<Session>,
     window_width: f32,
     window_height: f32,
 ) -> Option<GameState> {
     // Generate the maze if it's not already generated
-    if map_option.is_none() {
-        if let (Ok(mut width), Ok(mut height)) = (
+    if session.is_none() {
+        let (width, height, seed) = if let Some(difficulty) = new_game_state.difficulty {
+            // Search for a maze in the chosen band; it is rebuilt below from
+            // its size and seed so the carving gets recorded. The seed is
+            // random anyway, so a time limit keeps the window responsive.
+            let mut request =
+                DifficultyRequest::new(difficulty, new_game_state.algorithm, rand::random());
+            request.braid = new_game_state.braid;
+            request.budget = Some(Duration::from_secs(2));
+            let found = generate_for_difficulty(&request);
+            (found.width, found.height, found.metadata.seed)
+        } else if let (Ok(mut width), Ok(mut height)) = (
             new_game_state.width_input.parse::<usize>(),
             new_game_state.height_input.parse::<usize>(),
         ) {
@@ -406,45 +611,41 @@ fn new_game_enter_name(
                 new_game_state.height_input.clear();
                 return Some(GameState::NewGameEnterWidth(new_game_state.clone()));
             }
-
-            // Generate the map with specified dimensions
-            let mut new_map = Map::new(width, height);
-            new_map.generate_maze();
-            *map_option = Some(new_map);
+            (width, height, rand::random())
         } else {
             // Invalid input, return to width input
             new_game_state.width_input.clear();
             new_game_state.height_input.clear();
             return Some(GameState::NewGameEnterWidth(new_game_state.clone()));
-        }
+        };
+
+        // Generate the map with specified dimensions, recording the
+        // carving so it can be animated afterwards
+        let mut new_map = Map::new(width, height);
+        new_map.start_recording();
+        new_map.generate_maze(new_game_state.algorithm, seed);
+        new_map.braid(new_game_state.braid);
+        *session = Some(Session::new(new_map));
     }
 
     // Handle user input for map name
     if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
         if new_game_state.name_input.is_empty() {
-            // Assign default name
-            let mut index = 1;
-            loop {
-                let filename = format!("maps/map{}.bin", index);
-                if !Path::new(&filename).exists() {
-                    new_game_state.name_input = format!("map{}", index);
-                    break;
-                }
-                index += 1;
-            }
+            new_game_state.name_input = default_map_name();
         }
 
         let filename = format!("maps/{}.bin", new_game_state.name_input);
-        map_option
-            .as_ref()
-            .unwrap()
+        let current = session.as_mut().unwrap();
+        current.map.metadata.name = new_game_state.name_input.clone();
+        current
+            .map
             .save_to_file(&filename)
             .expect("Failed to save the map");
-        // Reset player position
-        *player = Player::new(1, 0);
-        visited_positions.clear();
-        visited_positions.insert(player.position);
-        return Some(GameState::Playing);
+        current.source = Some(filename);
+        // Show the maze being carved before playing
+        let map = &mut current.map;
+        let animation = GenerationAnimation::new(map.width, map.height, map.take_recording());
+        return Some(GameState::Generating(animation));
     } else if let Some(key) = rl.get_key_pressed() {
         if let Some(c) = key_to_char(key) {
             new_game_state.name_input.push(c);
@@ -512,13 +713,87 @@ fn new_game_enter_name(
     None
 }
 
+// Function for animating maze generation
+fn generating(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    animation: &mut GenerationAnimation,
+    maze_width: f32,
+    window_height: f32,
+) -> Option<GameState> {
+    // +/- change the speed, any other key skips to the finished maze
+    if let Some(key) = rl.get_key_pressed() {
+        match key {
+            KeyboardKey::KEY_EQUAL | KeyboardKey::KEY_KP_ADD => {
+                animation.speed = animation.speed.saturating_mul(2);
+            }
+            KeyboardKey::KEY_MINUS | KeyboardKey::KEY_KP_SUBTRACT => {
+                animation.speed = (animation.speed / 2).max(1);
+            }
+            _ => return Some(GameState::Playing(PlayView::default())),
+        }
+    }
+
+    animation.advance();
+
+    // Draw the maze carved so far
+    let mut d = rl.begin_drawing(thread);
+    d.clear_background(Color::WHITE);
+
+    let cell_size =
+        (maze_width / animation.width as f32).min(window_height / animation.height as f32);
+    for y in 0..animation.height {
+        for x in 0..animation.width {
+            if animation.grid[y * animation.width + x] == Cell::Wall {
+                let rect = Rectangle::new(
+                    x as f32 * cell_size,
+                    y as f32 * cell_size,
+                    cell_size,
+                    cell_size,
+                );
+                d.draw_rectangle_rec(rect, Color::BLACK);
+            }
+        }
+    }
+
+    // Draw UI panel
+    let ui_rect = Rectangle::new(maze_width, 0.0, 200.0, window_height);
+    d.draw_rectangle_rec(ui_rect, Color::LIGHTGRAY);
+    d.draw_text(
+        "Generating...",
+        (maze_width as i32) + 20,
+        20,
+        20,
+        Color::BLACK,
+    );
+    d.draw_text(
+        &format!("Speed: {}x (+/-)", animation.speed),
+        (maze_width as i32) + 20,
+        60,
+        20,
+        Color::BLACK,
+    );
+    d.draw_text(
+        "Any key to skip",
+        (maze_width as i32) + 20,
+        100,
+        20,
+        Color::DARKGRAY,
+    );
+
+    if animation.is_finished() {
+        return Some(GameState::Playing(PlayView::default()));
+    }
+
+    None
+}
+
 fn load_game(
     rl: &mut RaylibHandle,
     thread: &RaylibThread,
-    map_option: &mut Option<Map>,
-    player: &mut Player,
-    visited_positions: &mut HashSet<(usize, usize)>,
-    show_solution: &mut bool,
+    entries: &[MapEntry],
+    session: &mut Option<Session>,
+    solution: &mut SolutionView,
     window_width: f32,
     window_height: f32,
 ) -> Option<GameState> {
@@ -542,8 +817,7 @@ fn load_game(
     );
 
     // Check if maps are available
-    let maps = get_maps_list();
-    if maps.is_empty() {
+    if entries.is_empty() {
         let info_text = "No saved maps available.";
         let info_font_size = 20;
         let info_width = d.measure_text(info_text, info_font_size) as f32;
@@ -557,29 +831,74 @@ fn load_game(
     } else {
         // Handle input for map selection
         let start_y = 100.0;
-        for (i, map_file) in maps.iter().enumerate() {
+        for (i, entry) in entries.iter().enumerate() {
             let button_rect = Rectangle::new(
                 (window_width - 400.0) / 2.0,
-                start_y + i as f32 * 60.0,
+                start_y + i as f32 * 75.0,
                 400.0,
-                50.0,
+                45.0,
             );
             let button_hovered = button_rect.check_collision_point_rec(mouse_pos);
 
-            draw_button(&mut d, &button_rect, map_file, button_hovered);
+            draw_button(&mut d, &button_rect, entry.title(), button_hovered);
+
+            // Leaderboard of the map under the mouse
+            if button_hovered {
+                draw_leaderboard(
+                    &mut d,
+                    &entry.leaderboard,
+                    None,
+                    window_width - 260.0,
+                    100.0,
+                );
+            }
+
+            // Details under the button
+            let details = match &entry.metadata {
+                Some(metadata) => {
+                    let mut parts = vec![];
+                    if !metadata.author.is_empty() {
+                        parts.push(format!("by {}", metadata.author));
+                    }
+                    if metadata.created != 0 {
+                        parts.push(format_date(metadata.created));
+                    }
+                    parts.push(match metadata.algorithm {
+                        Some(algorithm) => algorithm.name().to_string(),
+                        None => "Hand-made".to_string(),
+                    });
+                    if let Some(score) = metadata.difficulty {
+                        parts.push(Difficulty::of_score(score).name().to_string());
+                    }
+                    parts.extend(metadata.tags.iter().map(|tag| format!("#{}", tag)));
+                    if let Some(best) = entry.leaderboard.scores.first() {
+                        parts.push(format!("best {} by {}", format_time(best.time), best.name));
+                    }
+                    parts.join("  ")
+                }
+                None => format!("{} (unreadable)", entry.filename),
+            };
+            let details_width = d.measure_text(&details, 15) as f32;
+            d.draw_text(
+                &details,
+                ((window_width - details_width) / 2.0) as i32,
+                (button_rect.y + 50.0) as i32,
+                15,
+                Color::DARKGRAY,
+            );
 
             if button_hovered && mouse_left_pressed {
                 // Load the selected map
-                let map_path = format!("maps/{}", map_file);
+                let map_path = format!("maps/{}", entry.filename);
                 match Map::load_from_file(&map_path) {
                     Ok(loaded_map) => {
-                        *map_option = Some(loaded_map);
-                        // Reset player position
-                        *player = Player::new(1, 0);
-                        visited_positions.clear();
-                        visited_positions.insert(player.position);
-                        *show_solution = false;
-                        return Some(GameState::Playing);
+                        // Start a fresh session on the loaded map
+                        let mut loaded = Session::new(loaded_map);
+                        loaded.source = Some(map_path);
+                        let view = PlayView::new(&loaded);
+                        *session = Some(loaded);
+                        solution.clear();
+                        return Some(GameState::Playing(view));
                     }
                     Err(e) => {
                         println!("Failed to load the map: {}", e);
@@ -611,115 +930,250 @@ fn playing(
     rl: &mut RaylibHandle,
     thread: &RaylibThread,
     delta_time: f32,
-    map_option: &mut Option<Map>,
-    player: &mut Player,
-    visited_positions: &mut HashSet<(usize, usize)>,
-    show_solution: &mut bool,
+    session: &mut Option<Session>,
+    view: &mut PlayView,
+    solution: &mut SolutionView,
     maze_width: f32,
     ui_width: f32,
     window_width: f32,
     window_height: f32,
 ) -> Option<GameState> {
-    // Ensure map is loaded
-    let map_ref = map_option.as_mut().unwrap();
+    // Ensure a session is running
+    let current = session.as_mut().unwrap();
+
+    // Seeing the solution rules the run out of the leaderboard
+    if solution.result.is_some() || solution.animation.is_some() {
+        current.assisted = true;
+    }
 
     // Handle input
-    if !player.is_moving {
-        if rl.is_key_down(KeyboardKey::KEY_UP) || rl.is_key_down(KeyboardKey::KEY_W) {
-            if player.position.1 > 0 {
-                let new_y = player.position.1 - 1;
-                if let Some(Cell::Path) | Some(Cell::Solution) =
-                    map_ref.get(player.position.0, new_y)
-                {
-                    player.direction = (0, -1);
-                    player.is_moving = true;
-                }
+    let direction = if rl.is_key_down(KeyboardKey::KEY_UP) || rl.is_key_down(KeyboardKey::KEY_W) {
+        Some(Direction::Up)
+    } else if rl.is_key_down(KeyboardKey::KEY_DOWN) || rl.is_key_down(KeyboardKey::KEY_S) {
+        Some(Direction::Down)
+    } else if rl.is_key_down(KeyboardKey::KEY_LEFT) || rl.is_key_down(KeyboardKey::KEY_A) {
+        Some(Direction::Left)
+    } else if rl.is_key_down(KeyboardKey::KEY_RIGHT) || rl.is_key_down(KeyboardKey::KEY_D) {
+        Some(Direction::Right)
+    } else {
+        None
+    };
+    let camera = &mut view.camera;
+    if direction.is_some() {
+        // Moving brings the view back to the player
+        camera.following = true;
+    }
+
+    // Update player position, from the keyboard or the replay being watched
+    if let Some(replay) = view.replay.as_mut() {
+        replay.update(current, delta_time);
+    } else {
+        current.set_input(direction);
+        current.update(delta_time);
+    }
+    if let Some(ghost) = view.ghost.as_mut() {
+        ghost.update(current);
+    }
+
+    // The run is over once the exit is reached; keep a recording of it, and
+    // its trace as the map's ghost if it is the fastest fair run
+    if current.is_won() && view.replay.is_none() {
+        if let Some(source) = &current.source {
+            let path = Recording::path_for(source);
+            if let Err(e) = current.recording().save(&path) {
+                println!("Failed to save the recording to {}: {}", path, e);
             }
-        } else if rl.is_key_down(KeyboardKey::KEY_DOWN) || rl.is_key_down(KeyboardKey::KEY_S) {
-            let new_y = player.position.1 + 1;
-            if let Some(Cell::Path) | Some(Cell::Solution) = map_ref.get(player.position.0, new_y) {
-                player.direction = (0, 1);
-                player.is_moving = true;
-            }
-        } else if rl.is_key_down(KeyboardKey::KEY_LEFT) || rl.is_key_down(KeyboardKey::KEY_A) {
-            if player.position.0 > 0 {
-                let new_x = player.position.0 - 1;
-                if let Some(Cell::Path) | Some(Cell::Solution) =
-                    map_ref.get(new_x, player.position.1)
-                {
-                    player.direction = (-1, 0);
-                    player.is_moving = true;
+            let path = Trace::path_for(source);
+            if !current.assisted {
+                if let Err(e) = current.trace().save_if_best(&path) {
+                    println!("Failed to save the ghost to {}: {}", path, e);
                 }
             }
-        } else if rl.is_key_down(KeyboardKey::KEY_RIGHT) || rl.is_key_down(KeyboardKey::KEY_D) {
-            let new_x = player.position.0 + 1;
-            if let Some(Cell::Path) | Some(Cell::Solution) = map_ref.get(new_x, player.position.1) {
-                player.direction = (1, 0);
-                player.is_moving = true;
-            }
         }
+        solution.clear();
+        return Some(GameState::Completed(Completion::new(current)));
     }
-
-    // Update player position
-    player.update_position(delta_time);
-    if !player.is_moving {
-        visited_positions.insert(player.position);
+    let map_ref = &current.map;
+    let player = &current.player;
+
+    // Advance the solver visualization; +/- change its speed
+    if let Some(animation) = solution.animation.as_mut() {
+        if rl.is_key_pressed(KeyboardKey::KEY_EQUAL) || rl.is_key_pressed(KeyboardKey::KEY_KP_ADD) {
+            animation.speed = (animation.speed * 2).min(256);
+        } else if rl.is_key_pressed(KeyboardKey::KEY_MINUS)
+            || rl.is_key_pressed(KeyboardKey::KEY_KP_SUBTRACT)
+        {
+            animation.speed = (animation.speed / 2).max(1);
+        }
+        if !animation.paused {
+            animation.advance(animation.speed);
+        }
     }
 
     // Cache values before drawing
     let mouse_pos = rl.get_mouse_position();
     let mouse_left_pressed = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
 
+    // Calculate cell size so the whole maze fits at zoom 1
+    let cell_size = (maze_width / map_ref.width as f32).min(window_height / map_ref.height as f32);
+    let maze_size = (
+        map_ref.width as f32 * cell_size,
+        map_ref.height as f32 * cell_size,
+    );
+    let view_centre = Vector2::new(maze_width / 2.0, window_height / 2.0);
+
+    // Mouse wheel zooms and dragging pans the maze; C recentres it
+    if rl.is_key_pressed(KeyboardKey::KEY_C) {
+        *camera = MazeCamera::default();
+    }
+
+    // F toggles fog of war
+    if rl.is_key_pressed(KeyboardKey::KEY_F) {
+        current.sight = match current.sight {
+            Some(_) => None,
+            None => Some(FOG_SIGHT),
+        };
+    }
+    let visible = current.visible_cells();
+    let is_visible = |position: (usize, usize)| {
+        visible
+            .as_ref()
+            .is_none_or(|visible| visible.contains(&position))
+    };
+    if mouse_pos.x < maze_width {
+        let wheel = rl.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            // Zoom about the player when following, else about the mouse
+            let anchor = if camera.following {
+                camera.target
+            } else {
+                let world = rl.get_screen_to_world2D(mouse_pos, camera_2d(camera, view_centre));
+                (world.x, world.y)
+            };
+            camera.zoom_at(1.25f32.powf(wheel), anchor);
+        }
+        let drag = rl.get_mouse_delta();
+        if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) && (drag.x, drag.y) != (0.0, 0.0)
+        {
+            camera.pan((drag.x, drag.y));
+        }
+    }
+    camera.update(
+        (
+            (player.render_position.0 + 0.5) * cell_size,
+            (player.render_position.1 + 0.5) * cell_size,
+        ),
+        maze_size,
+        (maze_width, window_height),
+    );
+    let raylib_camera = camera_2d(camera, view_centre);
+
+    // Only cells inside the view need drawing
+    let half_view = (
+        maze_width / camera.zoom / 2.0,
+        window_height / camera.zoom / 2.0,
+    );
+    let first_x = ((camera.target.0 - half_view.0) / cell_size).max(0.0) as usize;
+    let first_y = ((camera.target.1 - half_view.1) / cell_size).max(0.0) as usize;
+    let last_x = (((camera.target.0 + half_view.0) / cell_size).ceil() as usize).min(map_ref.width);
+    let last_y =
+        (((camera.target.1 + half_view.1) / cell_size).ceil() as usize).min(map_ref.height);
+
     // Begin drawing
     let mut d = rl.begin_drawing(thread);
     d.clear_background(Color::WHITE);
 
-    // Define maze and UI rectangles
-    let maze_rect = Rectangle::new(0.0, 0.0, maze_width, window_height);
+    // Define UI rectangle
     let ui_rect = Rectangle::new(maze_width, 0.0, ui_width, window_height);
 
-    // Calculate cell size
-    let cell_size = maze_width / map_ref.width as f32;
-
-    // Draw the maze
-    for y in 0..map_ref.height {
-        for x in 0..map_ref.width {
-            let rect = Rectangle::new(
-                maze_rect.x + x as f32 * cell_size,
-                maze_rect.y + y as f32 * cell_size,
+    // Draw the maze through the camera
+    {
+        let mut m = d.begin_mode2D(raylib_camera);
+        let cell_rect = |(x, y): (usize, usize)| {
+            Rectangle::new(
+                x as f32 * cell_size,
+                y as f32 * cell_size,
                 cell_size,
                 cell_size,
-            );
+            )
+        };
+        for y in first_y..last_y {
+            for x in first_x..last_x {
+                let rect = cell_rect((x, y));
+                if !is_visible((x, y)) {
+                    // Out of sight: visited cells are dimly remembered
+                    if current.visited.contains(&(x, y)) {
+                        m.draw_rectangle_rec(rect, Color::new(50, 80, 100, 255));
+                    } else {
+                        m.draw_rectangle_rec(rect, Color::DARKGRAY);
+                    }
+                } else if map_ref.get(x, y) == Some(Cell::Wall) {
+                    m.draw_rectangle_rec(rect, Color::BLACK);
+                } else if current.visited.contains(&(x, y)) {
+                    m.draw_rectangle_rec(rect, Color::SKYBLUE); // Visited path
+                } else {
+                    // Paths are left blank (white background)
+                }
+            }
+        }
 
-            if map_ref.get(x, y) == Some(Cell::Wall) {
-                d.draw_rectangle_rec(rect, Color::BLACK);
-            } else if *show_solution && map_ref.get(x, y) == Some(Cell::Solution) {
-                d.draw_rectangle_rec(rect, Color::YELLOW);
-            } else if visited_positions.contains(&(x, y)) {
-                d.draw_rectangle_rec(rect, Color::SKYBLUE); // Visited path
-            } else {
-                // Paths are left blank (white background)
+        // Draw the solver visualization: visited, frontier, then the final path
+        if let Some(animation) = &solution.animation {
+            for &position in &animation.visited {
+                m.draw_rectangle_rec(cell_rect(position), Color::PINK);
+            }
+            for &position in &animation.frontier {
+                m.draw_rectangle_rec(cell_rect(position), Color::ORANGE);
+            }
+            if animation.is_finished() {
+                for &position in animation.result.path.iter().flatten() {
+                    m.draw_rectangle_rec(cell_rect(position), Color::GOLD);
+                }
             }
         }
-    }
 
-    // Draw the player
-    let player_rect = Rectangle::new(
-        maze_rect.x + player.render_position.0 * cell_size,
-        maze_rect.y + player.render_position.1 * cell_size,
-        cell_size,
-        cell_size,
-    );
-    d.draw_rectangle_rec(player_rect, Color::RED);
+        // Draw the solution overlay, leaving the start and exit unmarked
+        if let Some(path) = solution
+            .result
+            .as_ref()
+            .and_then(|result| result.path.as_ref())
+        {
+            for &position in path.iter().skip(1).take(path.len().saturating_sub(2)) {
+                m.draw_rectangle_rec(cell_rect(position), Color::YELLOW);
+            }
+        }
 
-    // Draw the exit
-    let exit_rect = Rectangle::new(
-        maze_rect.x + (map_ref.width - 2) as f32 * cell_size,
-        maze_rect.y + (map_ref.height - 1) as f32 * cell_size,
-        cell_size,
-        cell_size,
-    );
-    d.draw_rectangle_rec(exit_rect, Color::GREEN);
+        // Draw the ghost under the player, when in sight
+        let ghost = view.ghost.as_ref().filter(|ghost| {
+            is_visible((
+                ghost.position.0.round() as usize,
+                ghost.position.1.round() as usize,
+            ))
+        });
+        if let Some(ghost) = ghost {
+            let ghost_rect = Rectangle::new(
+                ghost.position.0 * cell_size,
+                ghost.position.1 * cell_size,
+                cell_size,
+                cell_size,
+            );
+            m.draw_rectangle_rec(ghost_rect, Color::PURPLE.fade(0.4));
+        }
+
+        // Draw the player
+        let player_rect = Rectangle::new(
+            player.render_position.0 * cell_size,
+            player.render_position.1 * cell_size,
+            cell_size,
+            cell_size,
+        );
+        m.draw_rectangle_rec(player_rect, Color::RED);
+
+        // Draw the exits that are in sight
+        for &goal in map_ref.goals.iter().filter(|&&goal| is_visible(goal)) {
+            m.draw_rectangle_rec(cell_rect(goal), Color::GREEN);
+        }
+    }
 
     // Draw UI background
     d.draw_rectangle_rec(ui_rect, Color::LIGHTGRAY);
@@ -733,6 +1187,13 @@ fn playing(
         20,
         Color::BLACK,
     );
+    d.draw_text(
+        "Wheel/drag zoom, C centre, F fog",
+        (maze_width as i32) + 20,
+        82,
+        14,
+        Color::DARKGRAY,
+    );
 
     // Display player position
     d.draw_text(
@@ -743,25 +1204,176 @@ fn playing(
         Color::BLACK,
     );
 
-    // Optionally, display "You Win!" message
-    if player.position.0 == map_ref.width - 2 && player.position.1 == map_ref.height - 1 {
-        d.draw_text("You Win!", (maze_width as i32) + 50, 150, 30, Color::BLUE);
+    // Display the run so far
+    d.draw_text(
+        &format!("Time: {}", format_time(current.elapsed)),
+        (maze_width as i32) + 20,
+        130,
+        20,
+        Color::BLACK,
+    );
+    d.draw_text(
+        &format!("Moves: {}", current.moves),
+        (maze_width as i32) + 20,
+        155,
+        20,
+        Color::BLACK,
+    );
+    if let Some(replay) = &view.replay {
+        let replay_text = if replay.is_finished(current) {
+            "Replay finished"
+        } else {
+            "Watching replay"
+        };
+        d.draw_text(replay_text, (maze_width as i32) + 20, 178, 18, Color::BLUE);
+    } else if let Some(ghost) = &view.ghost {
+        // How the run compares with the ghost at the last cell both reached
+        let (ghost_text, ghost_color) = match ghost.delta {
+            Some(delta) if delta <= 0.0 => (format!("Ahead by {:.1}s", -delta), Color::DARKGREEN),
+            Some(delta) => (format!("Behind by {:.1}s", delta), Color::MAROON),
+            None => (
+                format!("Ghost: {}", format_time(ghost.trace.duration())),
+                Color::PURPLE,
+            ),
+        };
+        d.draw_text(&ghost_text, (maze_width as i32) + 20, 178, 18, ghost_color);
+    }
+
+    // Display live statistics while visualizing
+    if let Some(animation) = &solution.animation {
+        let mut lines = vec![
+            format!("Expanded: {}", animation.visited.len()),
+            format!("Frontier: {}", animation.frontier.len()),
+            format!("Speed: {}x (+/-)", animation.speed),
+        ];
+        if animation.is_finished() {
+            lines.push(match animation.result.path_length() {
+                Some(length) => format!("Path length: {}", length),
+                None => "No path found".to_string(),
+            });
+        }
+        for (i, text) in lines.iter().enumerate() {
+            d.draw_text(
+                text,
+                (maze_width as i32) + 20,
+                200 + i as i32 * 25,
+                20,
+                Color::BLACK,
+            );
+        }
+    }
+
+    // Display solver statistics while the solution is shown
+    if let Some(result) = &solution.result {
+        let length_text = match result.path_length() {
+            Some(length) => format!("Path length: {}", length),
+            None => "No path found".to_string(),
+        };
+        let optimal_text = match solution.optimal_length {
+            Some(length) => format!("Shortest: {}", length),
+            None => "Shortest: -".to_string(),
+        };
+        let expanded_text = format!("Expanded: {}", result.nodes_expanded);
+        for (i, text) in [length_text, optimal_text, expanded_text]
+            .iter()
+            .enumerate()
+        {
+            d.draw_text(
+                text,
+                (maze_width as i32) + 20,
+                200 + i as i32 * 25,
+                20,
+                Color::BLACK,
+            );
+        }
+    }
+
+    // Otherwise describe the maze itself
+    if solution.animation.is_none() && solution.result.is_none() {
+        let metrics = &current.metrics;
+        let lines = [
+            match metrics.solution_length {
+                Some(length) => format!("Solution: {}", length),
+                None => "Unsolvable".to_string(),
+            },
+            format!("Dead ends: {}", metrics.dead_ends),
+            format!("Junctions: {}", metrics.junctions),
+            format!("Branching: {:.2}", metrics.branching_factor),
+            format!("Corridor: {:.1}", metrics.average_corridor_length),
+            format!("River: {:.1}", metrics.river_factor),
+            match metrics.tortuosity {
+                Some(tortuosity) => format!("Tortuosity: {:.1}", tortuosity),
+                None => "Tortuosity: -".to_string(),
+            },
+            format!("On solution: {:.0}%", metrics.solution_coverage),
+        ];
+        for (i, text) in lines.iter().enumerate() {
+            d.draw_text(
+                text,
+                (maze_width as i32) + 20,
+                200 + i as i32 * 22,
+                18,
+                Color::DARKGRAY,
+            );
+        }
     }
 
     // Define button rectangles
     let button_width = ui_width - 40.0;
     let button_height = 40.0;
     let button_x = maze_width + 20.0;
+    let pause_button_rect =
+        Rectangle::new(button_x, window_height - 400.0, button_width, button_height);
+    let step_button_rect =
+        Rectangle::new(button_x, window_height - 350.0, button_width, button_height);
+    let reset_button_rect =
+        Rectangle::new(button_x, window_height - 300.0, button_width, button_height);
+    let visualize_button_rect =
+        Rectangle::new(button_x, window_height - 250.0, button_width, button_height);
+    let solver_button_rect =
+        Rectangle::new(button_x, window_height - 200.0, button_width, button_height);
     let solution_button_rect =
         Rectangle::new(button_x, window_height - 150.0, button_width, button_height);
     let menu_button_rect =
         Rectangle::new(button_x, window_height - 100.0, button_width, button_height);
 
+    let pause_button_hovered = pause_button_rect.check_collision_point_rec(mouse_pos);
+    let step_button_hovered = step_button_rect.check_collision_point_rec(mouse_pos);
+    let reset_button_hovered = reset_button_rect.check_collision_point_rec(mouse_pos);
+    let visualize_button_hovered = visualize_button_rect.check_collision_point_rec(mouse_pos);
+    let solver_button_hovered = solver_button_rect.check_collision_point_rec(mouse_pos);
     let solution_button_hovered = solution_button_rect.check_collision_point_rec(mouse_pos);
     let menu_button_hovered = menu_button_rect.check_collision_point_rec(mouse_pos);
 
+    // Draw visualization controls
+    if let Some(animation) = &solution.animation {
+        let pause_text = if animation.paused { "Resume" } else { "Pause" };
+        draw_button(&mut d, &pause_button_rect, pause_text, pause_button_hovered);
+        draw_button(&mut d, &step_button_rect, "Step", step_button_hovered);
+        draw_button(&mut d, &reset_button_rect, "Reset", reset_button_hovered);
+    }
+    let visualize_text = if solution.animation.is_some() {
+        "Stop Visualizing"
+    } else {
+        "Visualize"
+    };
+    draw_button(
+        &mut d,
+        &visualize_button_rect,
+        visualize_text,
+        visualize_button_hovered,
+    );
+
+    // Draw Solver button
+    draw_button(
+        &mut d,
+        &solver_button_rect,
+        solution.solver.name(),
+        solver_button_hovered,
+    );
+
     // Draw Show Solution button
-    let solution_text = if *show_solution {
+    let solution_text = if solution.result.is_some() {
         "Hide Solution"
     } else {
         "Show Solution"
@@ -778,20 +1390,277 @@ fn playing(
 
     // Handle button clicks
     if mouse_left_pressed {
-        if solution_button_hovered {
-            // Toggle show_solution
-            *show_solution = !*show_solution;
-            if *show_solution {
-                map_ref.solve_maze();
+        let animating = solution.animation.is_some();
+        if animating && pause_button_hovered {
+            if let Some(animation) = solution.animation.as_mut() {
+                animation.paused = !animation.paused;
+            }
+        } else if animating && step_button_hovered {
+            // Stepping pauses so the single step stays visible
+            if let Some(animation) = solution.animation.as_mut() {
+                animation.paused = true;
+                animation.advance(1);
+            }
+        } else if animating && reset_button_hovered {
+            if let Some(animation) = solution.animation.as_mut() {
+                animation.reset();
+            }
+        } else if visualize_button_hovered {
+            // Toggle the step-by-step visualization
+            if animating {
+                solution.clear();
+            } else {
+                solution.visualize(map_ref);
+            }
+        } else if solver_button_hovered {
+            // Cycle solvers, restarting whatever is shown
+            solution.solver = solution.solver.next();
+            if solution.result.is_some() {
+                solution.show(map_ref);
+            } else if animating {
+                solution.visualize(map_ref);
+            }
+        } else if solution_button_hovered {
+            // Toggle the solution overlay
+            if solution.result.is_some() {
+                solution.clear();
+            } else {
+                solution.show(map_ref);
             }
         } else if menu_button_hovered {
             // Go back to main menu
-            *map_option = None;
-            visited_positions.clear();
-            *show_solution = false;
+            *session = None;
+            solution.clear();
             return Some(GameState::MainMenu);
         }
     }
 
     None
 }
+
+// Raylib camera for a maze view centred on `view_centre`
+fn camera_2d(camera: &MazeCamera, view_centre: Vector2) -> Camera2D {
+    Camera2D {
+        offset: view_centre,
+        target: Vector2::new(camera.target.0, camera.target.1),
+        rotation: 0.0,
+        zoom: camera.zoom,
+    }
+}
+
+// Function for the screen shown on reaching the exit
+fn completed(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    completion: &mut Completion,
+    session: &mut Option<Session>,
+    solution: &mut SolutionView,
+    window_width: f32,
+) -> Option<GameState> {
+    let mouse_pos = rl.get_mouse_position();
+    let mouse_left_pressed = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+    // Define button rectangles
+    let button_width = 300.0;
+    let button_height = 50.0;
+    let button_x = (window_width - button_width) / 2.0;
+    let replay_button_rect = Rectangle::new(button_x, 450.0, button_width, button_height);
+    let watch_button_rect = Rectangle::new(button_x, 520.0, button_width, button_height);
+    let next_button_rect = Rectangle::new(button_x, 590.0, button_width, button_height);
+    let menu_button_rect = Rectangle::new(button_x, 660.0, button_width, button_height);
+
+    let replay_hovered = replay_button_rect.check_collision_point_rec(mouse_pos);
+    let watch_hovered = watch_button_rect.check_collision_point_rec(mouse_pos);
+    let next_hovered = next_button_rect.check_collision_point_rec(mouse_pos);
+    let menu_hovered = menu_button_rect.check_collision_point_rec(mouse_pos);
+
+    let current = session.as_mut().unwrap();
+
+    // Type a name and press Enter to record the run
+    if !completion.submitted && !current.assisted {
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            completion.submit(current);
+        } else if let Some(key) = rl.get_key_pressed() {
+            if let Some(c) = key_to_char(key).or_else(|| key_to_digit(key)) {
+                completion.name_input.push(c);
+            } else if key == KeyboardKey::KEY_BACKSPACE {
+                completion.name_input.pop();
+            }
+        }
+    }
+
+    if mouse_left_pressed {
+        if replay_hovered {
+            // Play the same maze again from the start, still unranked if the
+            // solution was shown
+            current.restart();
+            return Some(GameState::Playing(PlayView::new(current)));
+        } else if watch_hovered {
+            // Play the run back from its recording
+            let recording = current.recording();
+            current.restart();
+            let replay = Replay::new(recording, &current.map).ok();
+            return Some(GameState::Playing(PlayView {
+                replay,
+                ..PlayView::default()
+            }));
+        } else if next_hovered {
+            // Carve a fresh maze like this one and save it
+            let mut new_map = next_maze(&current.map);
+            new_map.metadata.name = default_map_name();
+            let filename = format!("maps/{}.bin", new_map.metadata.name);
+            new_map
+                .save_to_file(&filename)
+                .expect("Failed to save the map");
+            let animation =
+                GenerationAnimation::new(new_map.width, new_map.height, new_map.take_recording());
+            let mut next = Session::new(new_map);
+            next.source = Some(filename);
+            next.sight = current.sight;
+            *session = Some(next);
+            return Some(GameState::Generating(animation));
+        } else if menu_hovered {
+            // Go back to main menu
+            *session = None;
+            solution.clear();
+            return Some(GameState::MainMenu);
+        }
+    }
+
+    // Draw the results
+    let mut d = rl.begin_drawing(thread);
+    d.clear_background(Color::WHITE);
+
+    let title_text = "Maze Complete!";
+    let title_font_size = 50;
+    let title_width = d.measure_text(title_text, title_font_size) as f32;
+    d.draw_text(
+        title_text,
+        ((window_width - title_width) / 2.0) as i32,
+        120,
+        title_font_size,
+        Color::DARKBLUE,
+    );
+
+    let lines = [
+        format!("Time: {}", format_time(current.elapsed)),
+        format!("Moves: {}", current.moves),
+        format!("Backtracks: {}", current.backtracks),
+        match current.metrics.solution_length {
+            Some(length) => format!("Shortest route: {}", length),
+            None => "Shortest route: -".to_string(),
+        },
+        match current.efficiency() {
+            Some(efficiency) => format!("Efficiency: {:.0}%", efficiency),
+            None => "Efficiency: -".to_string(),
+        },
+    ];
+    for (i, text) in lines.iter().enumerate() {
+        let text_width = d.measure_text(text, 25) as f32;
+        d.draw_text(
+            text,
+            ((window_width - text_width) / 2.0) as i32,
+            200 + i as i32 * 35,
+            25,
+            Color::BLACK,
+        );
+    }
+
+    // Name entry, or what became of the run
+    let record_text = if current.assisted {
+        "Solution was shown - not ranked".to_string()
+    } else if !completion.submitted {
+        format!("Name: {}_  (ENTER to save)", completion.name_input)
+    } else {
+        match completion.rank {
+            Some(rank) => format!("Saved as #{}", rank + 1),
+            None => "Not fast enough for the leaderboard".to_string(),
+        }
+    };
+    let record_width = d.measure_text(&record_text, 20) as f32;
+    d.draw_text(
+        &record_text,
+        ((window_width - record_width) / 2.0) as i32,
+        385,
+        20,
+        Color::DARKGRAY,
+    );
+
+    // The map's leaderboard, with this run highlighted
+    draw_leaderboard(
+        &mut d,
+        &completion.leaderboard,
+        completion.rank,
+        window_width - 260.0,
+        200.0,
+    );
+
+    draw_button(&mut d, &replay_button_rect, "Replay", replay_hovered);
+    draw_button(&mut d, &watch_button_rect, "Watch Run", watch_hovered);
+    draw_button(&mut d, &next_button_rect, "Next Maze", next_hovered);
+    draw_button(&mut d, &menu_button_rect, "Menu", menu_hovered);
+
+    None
+}
+
+// A new maze of the same size, algorithm and braiding as `map`, with the
+// carving recorded for the generation animation
+fn next_maze(map: &Map) -> Map {
+    let algorithm = map
+        .metadata
+        .algorithm
+        .unwrap_or(Algorithm::RecursiveBacktracker);
+    let mut new_map = Map::new(map.width, map.height);
+    new_map.start_recording();
+    new_map.generate_maze(algorithm, rand::random());
+    new_map.braid(map.metadata.braid);
+    new_map
+}
+
+// First unused "mapN" name in the maps directory
+fn default_map_name() -> String {
+    let mut index = 1;
+    while Path::new(&format!("maps/map{}.bin", index)).exists() {
+        index += 1;
+    }
+    format!("map{}", index)
+}
+
+// Seconds as minutes, seconds and tenths
+fn format_time(seconds: f32) -> String {
+    format!("{}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0)
+}
+
+// Draw the top runs of a leaderboard as a column starting at (x, y)
+fn draw_leaderboard(
+    d: &mut RaylibDrawHandle,
+    leaderboard: &Leaderboard,
+    highlight: Option<usize>,
+    x: f32,
+    y: f32,
+) {
+    d.draw_text("Best runs", x as i32, y as i32, 20, Color::BLACK);
+    if leaderboard.scores.is_empty() {
+        d.draw_text("None yet", x as i32, y as i32 + 30, 18, Color::GRAY);
+    }
+    for (i, score) in leaderboard.scores.iter().take(5).enumerate() {
+        let color = if highlight == Some(i) {
+            Color::BLUE
+        } else {
+            Color::DARKGRAY
+        };
+        d.draw_text(
+            &format!(
+                "{}. {}  {}  {} moves",
+                i + 1,
+                score.name,
+                format_time(score.time),
+                score.moves
+            ),
+            x as i32,
+            y as i32 + 30 + i as i32 * 25,
+            18,
+            color,
+        );
+    }
+}
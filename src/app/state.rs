@@ -1,16 +1,332 @@
+use crate::utils::get_maps_list;
+use mazing_core::metadata::{current_user, now};
+use mazing_core::solvers::SearchEvent;
+use mazing_core::{
+    Algorithm, CarveEvent, Cell, Difficulty, Ghost, Leaderboard, Map, Metadata, Replay, Score,
+    Session, SolveResult, SolverKind,
+};
+use std::collections::HashSet;
+
 #[derive(Clone, PartialEq)]
 pub enum GameState {
     MainMenu,
+    NewGameSelectDifficulty(NewGameState),
     NewGameEnterWidth(NewGameState),
     NewGameEnterHeight(NewGameState),
+    NewGameSelectAlgorithm(NewGameState),
     NewGameEnterName(NewGameState),
-    LoadGame,
-    Playing,
+    Generating(GenerationAnimation),
+    LoadGame(Vec<MapEntry>),
+    Playing(PlayView),
+    Completed(Completion),
 }
 
+// Where leaderboards are kept, next to the maps they belong to
+pub const SCORES_DIR: &str = "maps/scores";
+
 #[derive(Clone, PartialEq)]
 pub struct NewGameState {
     pub width_input: String,
     pub height_input: String,
     pub name_input: String,
+    pub difficulty: Option<Difficulty>, // None when the size is typed in
+    pub algorithm: Algorithm,
+    pub braid: f32,
+}
+
+// Furthest the maze can be zoomed in
+const MAX_ZOOM: f32 = 8.0;
+
+// How the maze is viewed while playing. At zoom 1 the whole maze fits in the
+// view; zoomed in, the view follows the player until it is dragged away.
+#[derive(Clone, PartialEq)]
+pub struct MazeCamera {
+    pub zoom: f32,
+    pub target: (f32, f32), // Maze point at the centre of the view, in pixels at zoom 1
+    pub following: bool,    // Keep the player in the centre of the view
+}
+
+impl Default for MazeCamera {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            target: (0.0, 0.0),
+            following: true,
+        }
+    }
+}
+
+impl MazeCamera {
+    // Zoom by a factor, keeping the maze point `anchor` still on screen
+    pub fn zoom_at(&mut self, factor: f32, anchor: (f32, f32)) {
+        let zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+        let scale = self.zoom / zoom;
+        self.target.0 = anchor.0 - (anchor.0 - self.target.0) * scale;
+        self.target.1 = anchor.1 - (anchor.1 - self.target.1) * scale;
+        self.zoom = zoom;
+    }
+
+    // Drag the view by a distance in screen pixels, which stops following
+    pub fn pan(&mut self, delta: (f32, f32)) {
+        self.target.0 -= delta.0 / self.zoom;
+        self.target.1 -= delta.1 / self.zoom;
+        self.following = false;
+    }
+
+    // Centre on the player if following, then keep the view over the maze.
+    // Along a side where the whole maze fits, it is centred instead.
+    pub fn update(&mut self, player: (f32, f32), maze_size: (f32, f32), view_size: (f32, f32)) {
+        if self.following {
+            self.target = player;
+        }
+        let clamp = |target: f32, maze: f32, view: f32| {
+            let half = view / self.zoom / 2.0;
+            if maze <= half * 2.0 {
+                maze / 2.0
+            } else {
+                target.clamp(half, maze - half)
+            }
+        };
+        self.target = (
+            clamp(self.target.0, maze_size.0, view_size.0),
+            clamp(self.target.1, maze_size.1, view_size.1),
+        );
+    }
+}
+
+// How the playing screen shows the session: through a camera, racing the
+// map's ghost, and driven by a recording instead of the keyboard when
+// watching a replay
+#[derive(Clone, Default, PartialEq)]
+pub struct PlayView {
+    pub camera: MazeCamera,
+    pub replay: Option<Replay>,
+    pub ghost: Option<Ghost>,
+}
+
+impl PlayView {
+    // Race the best run saved for the session's map, if any
+    pub fn new(session: &Session) -> Self {
+        Self {
+            ghost: session
+                .source
+                .as_ref()
+                .and_then(|source| Ghost::load(source, &session.map)),
+            ..Self::default()
+        }
+    }
+}
+
+// The completion screen: the name to record the run under and the map's
+// leaderboard
+#[derive(Clone, PartialEq)]
+pub struct Completion {
+    pub name_input: String,
+    pub leaderboard: Leaderboard,
+    pub submitted: bool,
+    pub rank: Option<usize>, // Place the run took, None if it didn't make the board
+}
+
+impl Completion {
+    // Read the leaderboard of the session's map
+    pub fn new(session: &Session) -> Self {
+        let leaderboard = Leaderboard::load(SCORES_DIR, &session.map).unwrap_or_else(|e| {
+            println!("Failed to read the leaderboard: {}", e);
+            Leaderboard::default()
+        });
+        Self {
+            name_input: current_user(),
+            leaderboard,
+            submitted: false,
+            rank: None,
+        }
+    }
+
+    // Record the session's run under the entered name and save the board.
+    // Runs where the solution was shown are never recorded.
+    pub fn submit(&mut self, session: &Session) {
+        if self.submitted || self.name_input.is_empty() {
+            return;
+        }
+        let Some(score) = Score::of_run(session, &self.name_input, now()) else {
+            return;
+        };
+        self.submitted = true;
+        // Someone else may have finished the map since the board was read
+        if let Ok(latest) = Leaderboard::load(SCORES_DIR, &session.map) {
+            self.leaderboard = latest;
+        }
+        self.rank = self.leaderboard.submit(score);
+        if self.rank.is_some() {
+            if let Err(e) = self.leaderboard.save(SCORES_DIR, &session.map) {
+                println!("Failed to save the leaderboard: {}", e);
+            }
+        }
+    }
+}
+
+// A saved map offered on the load screen
+#[derive(Clone, PartialEq)]
+pub struct MapEntry {
+    pub filename: String,
+    pub metadata: Option<Metadata>, // None if the file could not be read
+    pub leaderboard: Leaderboard,
+}
+
+impl MapEntry {
+    // Read the metadata of every map in the maps directory
+    pub fn scan() -> Vec<Self> {
+        get_maps_list()
+            .into_iter()
+            .map(
+                |filename| match Map::load_from_file(&format!("maps/{}", filename)) {
+                    Ok(map) => Self {
+                        filename,
+                        leaderboard: Leaderboard::load(SCORES_DIR, &map).unwrap_or_default(),
+                        metadata: Some(map.metadata),
+                    },
+                    Err(_) => Self {
+                        filename,
+                        metadata: None,
+                        leaderboard: Leaderboard::default(),
+                    },
+                },
+            )
+            .collect()
+    }
+
+    // Name to show for the map, falling back to its filename
+    pub fn title(&self) -> &str {
+        match &self.metadata {
+            Some(metadata) if !metadata.name.is_empty() => &metadata.name,
+            _ => &self.filename,
+        }
+    }
+}
+
+// Replays the carve events of a freshly generated maze
+#[derive(Clone, PartialEq)]
+pub struct GenerationAnimation {
+    pub width: usize,
+    pub height: usize,
+    pub grid: Vec<Cell>, // The maze as carved so far
+    pub events: Vec<CarveEvent>,
+    pub shown: usize, // Events applied so far
+    pub speed: usize, // Events applied per frame
+}
+
+impl GenerationAnimation {
+    pub fn new(width: usize, height: usize, events: Vec<CarveEvent>) -> Self {
+        // Aim for about three seconds at 60 FPS regardless of maze size
+        let speed = (events.len() / 180).max(1);
+        Self {
+            width,
+            height,
+            grid: vec![Cell::Wall; width * height],
+            events,
+            shown: 0,
+            speed,
+        }
+    }
+
+    // Apply the next `speed` events
+    pub fn advance(&mut self) {
+        for event in self.events.iter().skip(self.shown).take(self.speed) {
+            self.grid[event.y * self.width + event.x] = event.cell;
+        }
+        self.shown = (self.shown + self.speed).min(self.events.len());
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shown == self.events.len()
+    }
+}
+
+// Solver chosen in the side panel and the result currently on screen
+#[derive(Default)]
+pub struct SolutionView {
+    pub solver: SolverKind,
+    pub result: Option<SolveResult>,
+    pub optimal_length: Option<usize>, // Shortest possible route, for comparison
+    pub animation: Option<SolverAnimation>, // Step-by-step view of the search
+}
+
+impl SolutionView {
+    // Run the chosen solver and show its result
+    pub fn show(&mut self, map: &Map) {
+        self.animation = None;
+        self.result = Some(self.solver.solver().solve(map));
+        self.optimal_length = map.solve().map(|path| path.len() - 1);
+    }
+
+    // Replay the chosen solver's search step by step
+    pub fn visualize(&mut self, map: &Map) {
+        self.clear();
+        self.animation = Some(SolverAnimation::new(self.solver, map));
+    }
+
+    pub fn clear(&mut self) {
+        self.result = None;
+        self.optimal_length = None;
+        self.animation = None;
+    }
+}
+
+// Replays a solver's recorded search a few steps per frame
+pub struct SolverAnimation {
+    pub events: Vec<SearchEvent>,
+    pub result: SolveResult,
+    pub shown: usize, // Events applied so far
+    pub speed: usize, // Expansions applied per frame
+    pub paused: bool,
+    pub frontier: HashSet<(usize, usize)>,
+    pub visited: HashSet<(usize, usize)>,
+}
+
+impl SolverAnimation {
+    pub fn new(solver: SolverKind, map: &Map) -> Self {
+        let (result, events) = solver.solver().solve_traced(map);
+        Self {
+            events,
+            result,
+            shown: 0,
+            speed: 1,
+            paused: false,
+            frontier: HashSet::new(),
+            visited: HashSet::new(),
+        }
+    }
+
+    // Apply up to `count` more expansions, along with the cells each one
+    // adds to the frontier
+    pub fn advance(&mut self, count: usize) {
+        let mut remaining = count;
+        while let Some(&event) = self.events.get(self.shown) {
+            match event {
+                SearchEvent::Frontier(position) => {
+                    self.frontier.insert(position);
+                }
+                SearchEvent::Visit(position) => {
+                    if remaining == 0 {
+                        break;
+                    }
+                    remaining -= 1;
+                    self.frontier.remove(&position);
+                    self.visited.insert(position);
+                }
+            }
+            self.shown += 1;
+        }
+    }
+
+    // Go back to before the first step
+    pub fn reset(&mut self) {
+        self.shown = 0;
+        self.frontier.clear();
+        self.visited.clear();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shown == self.events.len()
+    }
 }
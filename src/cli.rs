@@ -0,0 +1,419 @@
+use mazing_core::book::puzzle_book;
+use mazing_core::difficulty::generate_for_difficulty;
+use mazing_core::metadata::format_date;
+use mazing_core::{
+    Algorithm, BookOptions, Difficulty, DifficultyRequest, Map, Recording, Replay, Session,
+    SolverKind, SvgOptions,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+
+const USAGE: &str = "\
+Usage:
+  mazing [MAP]                Open the game, optionally playing MAP
+  mazing generate [OPTIONS]   Generate a maze and save it
+      --width N --height N    Maze size (default 21x21)
+      --difficulty LEVEL      easy, medium, hard or expert; picks the size, so
+                              --width and --height can't be given with it
+      --seed N                Seed (default: random)
+      --algo NAME             Generation algorithm (default: backtracker)
+      --braid F               Fraction of dead ends to remove, 0 to 1
+      --name TEXT --author TEXT
+                              Display name and author (default: current user)
+      --tags A,B              Comma-separated tags
+      --out PATH              Where to save the map (required)
+  mazing solve MAP [--solver NAME]
+                              Solve a map and print the route
+  mazing stats MAP            Print information about a map
+  mazing render MAP --format ascii|svg|png [--out PATH] [--solution] [--scale N]
+                              Render a map to stdout or a file
+      --scale N               Pixels per cell (default 10)
+      --stroke F --margin F   SVG wall thickness and border
+      --wall-color C --background C --solution-color C
+                              SVG colours
+  mazing book [OPTIONS]       Make a printable PDF of mazes with answer pages
+      --count N --title TEXT  Number of mazes (default 10) and book title
+      --width N --height N --seed N --algo NAME --braid F
+                              As for generate (default 21x29, seed 0)
+      --out PATH              Where to save the PDF (required)
+  mazing upgrade MAP...       Rewrite maps in the current file format
+  mazing convert MAP OUT      Convert between .bin maps, .txt ASCII maps and
+                              .png images
+  mazing replay MAP [--recording PATH]
+                              Play back the run recorded on MAP (default
+                              MAP.replay) and print its result
+";
+
+// Whether the first argument selects a headless subcommand
+pub fn is_subcommand(arg: &str) -> bool {
+    matches!(
+        arg,
+        "generate"
+            | "solve"
+            | "stats"
+            | "render"
+            | "book"
+            | "upgrade"
+            | "convert"
+            | "replay"
+            | "help"
+            | "--help"
+            | "-h"
+    )
+}
+
+// Run a subcommand without opening a window; returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let result = match args[0].as_str() {
+        "generate" => generate(&args[1..]),
+        "solve" => solve(&args[1..]),
+        "stats" => stats(&args[1..]),
+        "render" => render(&args[1..]),
+        "book" => book(&args[1..]),
+        "upgrade" => upgrade(&args[1..]),
+        "convert" => convert(&args[1..]),
+        "replay" => replay(&args[1..]),
+        _ => {
+            print!("{}", USAGE);
+            return 0;
+        }
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(message) => fail(&message),
+    }
+}
+
+// Report a command line error with the usage; returns the process exit code
+pub fn fail(message: &str) -> i32 {
+    eprintln!("error: {}", message);
+    eprint!("\n{}", USAGE);
+    1
+}
+
+// Positional arguments and `--name value` options
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    // Options listed in `switches` take no value
+    fn parse(args: &[String], switches: &[&str]) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = if switches.contains(&name) {
+                    String::new()
+                } else {
+                    iter.next()
+                        .ok_or_else(|| format!("missing value for --{}", name))?
+                        .clone()
+                };
+                options.insert(name.to_string(), value);
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    // Parse an option's value, falling back to `default` when absent
+    fn get<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.options.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("invalid value '{}' for --{}", value, name)),
+            None => Ok(default),
+        }
+    }
+
+    // Load the map named by the first positional argument
+    fn map(&self) -> Result<Map, String> {
+        let path = self.positional.first().ok_or("missing map path")?;
+        Map::load_from_file(path).map_err(|e| format!("failed to load {}: {}", path, e))
+    }
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let width = args.get("width", 21)?;
+    let height = args.get("height", 21)?;
+    let seed = args.get("seed", rand::random())?;
+    let braid: f32 = args.get("braid", 0.0)?;
+    let algorithm = match args.options.get("algo") {
+        Some(name) => name.parse::<Algorithm>()?,
+        None => Algorithm::RecursiveBacktracker,
+    };
+    let out = args.options.get("out").ok_or("missing --out")?;
+
+    if width < 5 || height < 5 {
+        return Err("width and height must be at least 5".to_string());
+    }
+    if !(0.0..=1.0).contains(&braid) {
+        return Err("--braid must be between 0 and 1".to_string());
+    }
+
+    let difficulty = args.options.get("difficulty");
+    if difficulty.is_some()
+        && (args.options.contains_key("width") || args.options.contains_key("height"))
+    {
+        return Err("--difficulty picks the size; drop --width and --height".to_string());
+    }
+
+    let mut map = match difficulty {
+        Some(name) => {
+            let mut request = DifficultyRequest::new(name.parse::<Difficulty>()?, algorithm, seed);
+            request.braid = braid;
+            generate_for_difficulty(&request)
+        }
+        None => {
+            let mut map = Map::new(width, height);
+            map.generate_maze(algorithm, seed);
+            map.braid(braid);
+            map
+        }
+    };
+    if let Some(name) = args.options.get("name") {
+        map.metadata.name = name.clone();
+    }
+    if let Some(author) = args.options.get("author") {
+        map.metadata.author = author.clone();
+    }
+    if let Some(tags) = args.options.get("tags") {
+        map.metadata.tags = tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+    }
+    map.save_to_file(out)
+        .map_err(|e| format!("failed to save {}: {}", out, e))?;
+
+    // A difficulty search keeps the seed of the candidate it settled on,
+    // derived from the one given
+    let seed_text = if difficulty.is_some() {
+        format!("seed {} (candidate seed {})", seed, map.metadata.seed)
+    } else {
+        format!("seed {}", seed)
+    };
+    println!(
+        "Generated {}x{} {} maze with {} into {}",
+        map.width,
+        map.height,
+        algorithm.name(),
+        seed_text,
+        out
+    );
+    Ok(())
+}
+
+fn solve(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let map = args.map()?;
+    let kind = match args.options.get("solver") {
+        Some(name) => name.parse::<SolverKind>()?,
+        None => SolverKind::Bfs,
+    };
+
+    let result = kind.solver().solve(&map);
+    println!("Solver: {}", kind.name());
+    println!("Nodes expanded: {}", result.nodes_expanded);
+    match &result.path {
+        Some(path) => {
+            println!("Path length: {}", path.len() - 1);
+            print!("{}", map.to_ascii(Some(path)));
+            Ok(())
+        }
+        None => Err("no route from the start to a goal".to_string()),
+    }
+}
+
+fn stats(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let map = args.map()?;
+
+    let metadata = &map.metadata;
+    println!("Name: {}", metadata.name);
+    println!("Author: {}", metadata.author);
+    if metadata.created != 0 {
+        println!("Created: {}", format_date(metadata.created));
+    }
+    println!("Size: {}x{}", map.width, map.height);
+    match metadata.algorithm {
+        Some(algorithm) => {
+            println!("Algorithm: {}", algorithm.name());
+            println!("Seed: {}", metadata.seed);
+            println!("Braid: {}", metadata.braid);
+        }
+        None => println!("Algorithm: hand-made"),
+    }
+    if let Some(score) = metadata.difficulty {
+        println!(
+            "Difficulty: {} (score {:.0})",
+            Difficulty::of_score(score).name(),
+            score
+        );
+    }
+    if !metadata.tags.is_empty() {
+        println!("Tags: {}", metadata.tags.join(", "));
+    }
+    println!("Start: {:?}", map.start);
+    println!("Goals: {:?}", map.goals);
+
+    let metrics = map.metrics();
+    println!("Open cells: {}", metrics.open_cells);
+    match metrics.solution_length {
+        Some(length) => println!("Solution length: {}", length),
+        None => println!("Solution length: unsolvable"),
+    }
+    println!("Dead ends: {}", metrics.dead_ends);
+    println!("Junctions: {}", metrics.junctions);
+    println!("Branching factor: {:.2}", metrics.branching_factor);
+    println!(
+        "Average corridor length: {:.2}",
+        metrics.average_corridor_length
+    );
+    println!("River factor: {:.2}", metrics.river_factor);
+    if let Some(tortuosity) = metrics.tortuosity {
+        println!("Tortuosity: {:.2}", tortuosity);
+    }
+    println!("Cells on solution: {:.1}%", metrics.solution_coverage);
+    Ok(())
+}
+
+fn render(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["solution"])?;
+    let map = args.map()?;
+    let format = args.options.get("format").ok_or("missing --format")?;
+    let scale = args.get("scale", 10)?;
+
+    let solution = if args.has("solution") {
+        Some(map.solve().ok_or("the map has no solution")?)
+    } else {
+        None
+    };
+    let solution = solution.as_deref();
+
+    let bytes = match format.as_str() {
+        "ascii" => map.to_ascii(solution).into_bytes(),
+        "svg" => {
+            let defaults = SvgOptions::default();
+            let options = SvgOptions {
+                cell_size: scale as f32,
+                stroke_width: args.get("stroke", defaults.stroke_width)?,
+                margin: args.get("margin", defaults.margin)?,
+                wall_color: args.get("wall-color", defaults.wall_color)?,
+                background: args.get("background", defaults.background)?,
+                solution_color: args.get("solution-color", defaults.solution_color)?,
+            };
+            map.to_svg(&options, solution).into_bytes()
+        }
+        "png" => map
+            .to_png(scale, solution)
+            .map_err(|e| format!("failed to encode PNG: {}", e))?,
+        other => return Err(format!("unknown format '{}'", other)),
+    };
+
+    match args.options.get("out") {
+        Some(out) => fs::write(out, bytes).map_err(|e| format!("failed to write {}: {}", out, e)),
+        None => io::stdout()
+            .write_all(&bytes)
+            .map_err(|e| format!("failed to write output: {}", e)),
+    }
+}
+
+fn book(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let defaults = BookOptions::default();
+    let options = BookOptions {
+        title: args.get("title", defaults.title)?,
+        count: args.get("count", defaults.count)?,
+        width: args.get("width", defaults.width)?,
+        height: args.get("height", defaults.height)?,
+        algorithm: match args.options.get("algo") {
+            Some(name) => name.parse::<Algorithm>()?,
+            None => defaults.algorithm,
+        },
+        braid: args.get("braid", defaults.braid)?,
+        seed: args.get("seed", defaults.seed)?,
+    };
+    let out = args.options.get("out").ok_or("missing --out")?;
+
+    if options.width < 5 || options.height < 5 {
+        return Err("width and height must be at least 5".to_string());
+    }
+    if options.count == 0 {
+        return Err("--count must be at least 1".to_string());
+    }
+
+    fs::write(out, puzzle_book(&options)).map_err(|e| format!("failed to write {}: {}", out, e))?;
+    println!("Wrote {} mazes and their answers to {}", options.count, out);
+    Ok(())
+}
+
+fn upgrade(args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        return Err("missing map path".to_string());
+    }
+    // Loading accepts older formats and saving always writes the current one
+    for path in args {
+        let map =
+            Map::load_from_file(path).map_err(|e| format!("failed to load {}: {}", path, e))?;
+        map.save_to_file(path)
+            .map_err(|e| format!("failed to save {}: {}", path, e))?;
+        println!("Upgraded {}", path);
+    }
+    Ok(())
+}
+
+fn convert(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let map = args.map()?;
+    let out = args.positional.get(1).ok_or("missing output path")?;
+    map.save_to_file(out)
+        .map_err(|e| format!("failed to save {}: {}", out, e))?;
+    println!("Converted {} to {}", args.positional[0], out);
+    Ok(())
+}
+
+fn replay(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let map = args.map()?;
+    let path = match args.options.get("recording") {
+        Some(path) => path.clone(),
+        None => Recording::path_for(&args.positional[0]),
+    };
+    let recording =
+        Recording::load(&path).map_err(|e| format!("failed to load {}: {}", path, e))?;
+
+    let mut session = Session::new(map);
+    let mut replay = Replay::new(recording, &session.map).map_err(|e| e.to_string())?;
+    replay.finish(&mut session);
+
+    if session.is_won() {
+        println!(
+            "Reached the exit in {:.2}s with {} moves ({} backtracks)",
+            session.elapsed, session.moves, session.backtracks
+        );
+    } else {
+        println!(
+            "Stopped at {:?} after {:.2}s and {} moves without reaching the exit",
+            session.player.position,
+            replay.recording().duration(),
+            session.moves
+        );
+    }
+    Ok(())
+}
@@ -1,7 +0,0 @@
-pub mod cell;
-pub mod map;
-pub mod player;
-
-pub use cell::Cell;
-pub use map::Map;
-pub use player::Player;
@@ -1,14 +1,23 @@
 use std::env;
+use std::process;
 
+#[cfg(feature = "gui")]
 mod app;
-mod game;
+mod cli;
+#[cfg(feature = "gui")]
 mod ui;
+#[cfg(feature = "gui")]
 mod utils;
 
 fn main() {
     // Get command-line arguments
     let args: Vec<String> = env::args().collect();
 
+    // Subcommands run headless and never open a window
+    if args.len() > 1 && cli::is_subcommand(&args[1]) {
+        process::exit(cli::run(&args[1..]));
+    }
+
     // Check if a map path is provided
     let map_path = if args.len() > 1 {
         Some(args[1].clone())
@@ -16,5 +25,18 @@ fn main() {
         None
     };
 
+    #[cfg(feature = "gui")]
     app::run(map_path);
+
+    #[cfg(not(feature = "gui"))]
+    {
+        let message = match map_path {
+            Some(arg) => format!(
+                "unknown subcommand '{}'; built without the game window",
+                arg
+            ),
+            None => "no subcommand given; built without the game window".to_string(),
+        };
+        process::exit(cli::fail(&message));
+    }
 }
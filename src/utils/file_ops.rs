@@ -6,7 +6,7 @@ pub fn get_maps_list() -> Vec<String> {
     for path in paths {
         let path = path.unwrap().path();
         if let Some(extension) = path.extension() {
-            if extension == "bin" {
+            if extension == "bin" || extension == "txt" || extension == "png" {
                 if let Some(name) = path.file_name() {
                     maps.push(name.to_string_lossy().into_owned());
                 }